@@ -0,0 +1,43 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, ItemStruct};
+
+/// Generate a match arm which concisely displays one variant of a generated enum.
+/// The variant's name is printed along with any of its fields which are IDs, because
+/// those are what's most useful in a log. E.g. `Extrude(target=<uuid>)`.
+pub fn display_arm(enum_name: &Ident, item: &ItemStruct) -> TokenStream {
+    let variant = &item.ident;
+    let id_fields: Vec<_> = item
+        .fields
+        .iter()
+        .filter(|field| is_id_type(&field.ty))
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    if id_fields.is_empty() {
+        return quote! {
+            #enum_name::#variant(_) => f.write_str(stringify!(#variant)),
+        };
+    }
+    let fmt = format!(
+        "{variant}({})",
+        id_fields
+            .iter()
+            .map(|field| format!("{field}={{}}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    quote! {
+        #enum_name::#variant(x) => write!(f, #fmt, #(x.#id_fields),*),
+    }
+}
+
+/// Is this type (by name) one of the ID types used in modeling commands?
+fn is_id_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(ty) = ty else {
+        return false;
+    };
+    ty.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Uuid" || segment.ident == "ModelingCmdId")
+}
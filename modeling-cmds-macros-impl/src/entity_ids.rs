@@ -0,0 +1,58 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Match arm which lists every entity ID in the variant's fields, in field order.
+pub fn entity_ids_arm(enum_name: &syn::Ident, item: &syn::ItemStruct) -> TokenStream {
+    let variant = &item.ident;
+    let ids: Vec<_> = item
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            ids_in(&field.ty, quote!(x.#ident))
+        })
+        .collect();
+    if ids.is_empty() {
+        return quote! {
+            #enum_name::#variant(_) => Vec::new(),
+        };
+    }
+    quote! {
+        #enum_name::#variant(x) => {
+            let mut ids = Vec::new();
+            #(ids.extend(#ids);)*
+            ids
+        }
+    }
+}
+
+/// If this type (by name) is an ID, or an `Option`, `Vec`, set or array of IDs,
+/// returns an iterator over the IDs in `value` (an expression of that type).
+fn ids_in(ty: &syn::Type, value: TokenStream) -> Option<TokenStream> {
+    let ty = match ty {
+        syn::Type::Path(ty) => ty,
+        syn::Type::Array(array) => return collection_ids_in(&array.elem, value),
+        _ => return None,
+    };
+    let segment = ty.path.segments.last()?;
+    match segment.ident.to_string().as_str() {
+        "Uuid" => Some(quote!(std::iter::once(#value))),
+        "ModelingCmdId" => Some(quote!(std::iter::once((#value).0))),
+        "Option" | "Vec" | "BTreeSet" | "HashSet" => {
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            let Some(syn::GenericArgument::Type(inner)) = args.args.first() else {
+                return None;
+            };
+            collection_ids_in(inner, value)
+        }
+        _ => None,
+    }
+}
+
+/// If `value` is a collection of `inner`s which are (or contain) IDs, returns an iterator over those IDs.
+fn collection_ids_in(inner: &syn::Type, value: TokenStream) -> Option<TokenStream> {
+    let inner = ids_in(inner, quote!(*id))?;
+    Some(quote!((#value).iter().flat_map(|id| #inner)))
+}
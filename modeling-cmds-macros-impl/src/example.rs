@@ -0,0 +1,27 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implements `test_util::Example` for a modeling command, using an example of each of its fields.
+pub fn example_impl(item: &syn::ItemStruct) -> TokenStream {
+    let name = &item.ident;
+    let example = quote!(kittycad_modeling_cmds::test_util::Example::example());
+    let body = match &item.fields {
+        syn::Fields::Named(fields) => {
+            let fields = fields.named.iter().map(|field| &field.ident);
+            quote!(Self { #(#fields: #example,)* })
+        }
+        syn::Fields::Unnamed(fields) => {
+            let fields = fields.unnamed.iter().map(|_| &example);
+            quote!(Self(#(#fields),*))
+        }
+        syn::Fields::Unit => quote!(Self),
+    };
+    quote! {
+        #[cfg(any(test, feature = "test-util"))]
+        impl kittycad_modeling_cmds::test_util::Example for kittycad_modeling_cmds::each_cmd::#name {
+            fn example() -> Self {
+                #body
+            }
+        }
+    }
+}
@@ -1,5 +1,8 @@
 //! Proc-macros for implementing kittycad-modeling-cmds traits.
 
+mod display;
+mod entity_ids;
+mod example;
 pub mod modeling_cmd_enum;
 pub mod modeling_cmd_output;
 pub mod modeling_cmd_variant;
@@ -44,9 +44,22 @@ pub fn generate(input: ItemMod) -> TokenStream {
                 })
                 .collect::<Vec<_>>()
                 .join("\n");
-            Some((&item.ident, doc))
+            Some((item, doc))
         })
         .unzip();
+    let display_arms = variants
+        .iter()
+        .map(|item| crate::display::display_arm(&syn::parse_quote!(ModelingCmd), item))
+        .collect::<Vec<_>>();
+    let entity_ids_arms = variants
+        .iter()
+        .map(|item| crate::entity_ids::entity_ids_arm(&syn::parse_quote!(ModelingCmd), item))
+        .collect::<Vec<_>>();
+    let example_impls = variants
+        .iter()
+        .map(|item| crate::example::example_impl(item))
+        .collect::<Vec<_>>();
+    let variants = variants.iter().map(|item| &item.ident).collect::<Vec<_>>();
 
     // Output the generated enum.
     quote_spanned! {span=>
@@ -72,10 +85,46 @@ pub fn generate(input: ItemMod) -> TokenStream {
             #[doc = #docs]
             #variants,
         )*}
+        /// Shows the command's name, and the IDs it refers to.
+        impl std::fmt::Display for ModelingCmd {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+        impl ModelingCmd {
+            /// Every entity ID in this command's fields, e.g. to find which entities it depends on.
+            /// Only finds fields which are IDs, or an `Option`, `Vec` or set of IDs.
+            /// IDs nested inside other types (e.g. path segments) aren't included.
+            /// An ID may appear more than once.
+            pub fn referenced_entity_ids(&self) -> Vec<::uuid::Uuid> {
+                match self {
+                    #(#entity_ids_arms)*
+                }
+            }
+        }
+        #(#example_impls)*
+        #[cfg(any(test, feature = "test-util"))]
+        impl ModelingCmd {
+            /// An example of every modeling command, e.g. for golden files of their JSON.
+            pub fn examples() -> Vec<Self> {
+                vec![#(
+                    Self::#variants(kittycad_modeling_cmds::test_util::Example::example()),
+                )*]
+            }
+        }
         /// You can easily convert each modeling command with its fields,
         /// into a modeling command without fields.
         impl From<ModelingCmd> for ModelingCmdEndpoint {
             fn from(v: ModelingCmd) -> Self {
+                Self::from(&v)
+            }
+        }
+        /// Like the conversion from an owned command, but doesn't need the command to be
+        /// moved or cloned, which is expensive for large commands like `ImportFiles`.
+        impl From<&ModelingCmd> for ModelingCmdEndpoint {
+            fn from(v: &ModelingCmd) -> Self {
                 match v {#(
                     ModelingCmd::#variants(_) => Self::#variants,
                 )*}
@@ -13,11 +13,16 @@ pub fn generate(input: ItemMod) -> TokenStream {
         .filter_map(|item| {
             // All modeling commands are public structs.
             match item {
-                syn::Item::Struct(item) if matches!(item.vis, syn::Visibility::Public(_)) => Some(&item.ident),
+                syn::Item::Struct(item) if matches!(item.vis, syn::Visibility::Public(_)) => Some(item),
                 _ => None,
             }
         })
         .collect::<Vec<_>>();
+    let display_arms = variants
+        .iter()
+        .map(|item| crate::display::display_arm(&syn::parse_quote!(OkModelingCmdResponse), item))
+        .collect::<Vec<_>>();
+    let variants = variants.iter().map(|item| &item.ident).collect::<Vec<_>>();
 
     // Output the generated enum.
     quote_spanned! {span=>
@@ -34,6 +39,43 @@ pub fn generate(input: ItemMod) -> TokenStream {
             /// defined here.
             Empty,
             #(#[doc = concat!("The response to the '", stringify!(#variants), "' endpoint.")] #variants(output::#variants),)*
+            /// A response this version of the crate doesn't recognize, e.g. because the engine
+            /// added it after this crate was published. Kept as JSON, so nothing is lost.
+            #[serde(untagged)]
+            #[cfg_attr(feature = "derive-jsonschema-on-enums", schemars(skip))]
+            Unknown(UnknownOkModelingCmdResponse),
+        }
+
+        /// A response from a modeling command which this version of the crate doesn't recognize.
+        /// See [`OkModelingCmdResponse::Unknown`].
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        pub struct UnknownOkModelingCmdResponse {
+            /// The response's type, as sent by the engine.
+            #[serde(rename = "type", deserialize_with = "unknown_response_type")]
+            pub response_type: String,
+            /// The response's data, as sent by the engine.
+            #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+            pub data: serde_json::Value,
+        }
+
+        /// Only accept response types which aren't known.
+        /// If a known response type got this far, its data must have been invalid, and that should be
+        /// an error rather than an unknown response.
+        fn unknown_response_type<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "snake_case")]
+            enum KnownType {
+                Empty,
+                #(#variants,)*
+            }
+            let response_type = String::deserialize(deserializer)?;
+            let as_known = serde::de::value::StrDeserializer::<D::Error>::new(&response_type);
+            if KnownType::deserialize(as_known).is_ok() {
+                return Err(serde::de::Error::custom(format!(
+                    "invalid data for a `{response_type}` response"
+                )));
+            }
+            Ok(response_type)
         }
 
         // Loop over `variants`, generate N different `From` impls on the enum,
@@ -47,6 +89,17 @@ pub fn generate(input: ItemMod) -> TokenStream {
         }
         )*
 
+        /// Shows the response's name, and the IDs it contains.
+        impl std::fmt::Display for OkModelingCmdResponse {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Self::Empty => f.write_str("Empty"),
+                    #(#display_arms)*
+                    Self::Unknown(x) => write!(f, "Unknown({})", x.response_type),
+                }
+            }
+        }
+
         // The `Empty` enum variant is a bit different, doesn't conform to the same pattern.
         // So define it manually.
         impl From<()> for OkModelingCmdResponse {
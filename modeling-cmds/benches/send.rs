@@ -0,0 +1,82 @@
+//! Sending a command moves it into a `ModelingCmdReq` and encodes that, without copying the command first.
+//! For a large import, copying the command would copy every byte of the file.
+// `criterion_group!` generates an undocumented function.
+#![allow(missing_docs)]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use kittycad_modeling_cmds::{
+    format::InputFormat,
+    id::ModelingCmdId,
+    shared::FileImportFormat,
+    websocket::{ModelingCmdReq, WebSocketRequest},
+    ImportFile, ImportFiles, ModelingCmd,
+};
+
+/// Counts how many bytes are allocated, to compare how much each way of sending allocates.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn import_files() -> ModelingCmd {
+    ModelingCmd::ImportFiles(ImportFiles {
+        files: vec![ImportFile {
+            path: "big.stl".to_owned(),
+            data: vec![0; 10 * 1024 * 1024],
+        }],
+        format: InputFormat::from(FileImportFormat::Stl),
+    })
+}
+
+/// Build the request for a command and encode it as BSON, like the session does for imports.
+fn send(cmd: ModelingCmd) -> Vec<u8> {
+    let req = WebSocketRequest::ModelingCmdReq(ModelingCmdReq {
+        cmd,
+        cmd_id: ModelingCmdId(uuid::Uuid::nil()),
+    });
+    bson::to_vec(&req).unwrap()
+}
+
+fn bytes_allocated(f: impl FnOnce()) -> usize {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    f();
+    ALLOCATED.load(Ordering::Relaxed) - before
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let cmd = import_files();
+    let cloned = bytes_allocated(|| drop(send(cmd.clone())));
+    let moved = bytes_allocated(|| drop(send(cmd)));
+    println!("Bytes allocated to send a 10 MiB ImportFiles: {cloned} when cloned, {moved} when moved");
+
+    let mut group = c.benchmark_group("send a 10 MiB ImportFiles");
+    group.bench_function("cloned", |b| {
+        let cmd = import_files();
+        b.iter(|| send(black_box(&cmd).clone()))
+    });
+    group.bench_function("moved", |b| {
+        b.iter_batched(import_files, |cmd| send(black_box(cmd)), BatchSize::LargeInput)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
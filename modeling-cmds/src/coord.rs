@@ -2,6 +2,8 @@ use parse_display::{Display, FromStr};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::shared::Point3d;
+
 /// Co-ordinate axis specifier.
 ///
 /// See [cglearn.eu] for background reading.
@@ -169,9 +171,78 @@ pub const VULKAN: &System = &System {
 /// ```
 #[inline]
 pub fn transform(a: [f32; 3], from: &System, to: &System) -> [f32; 3] {
+    transform_components(a, from, to)
+}
+
+fn transform_components<T>(a: [T; 3], from: &System, to: &System) -> [T; 3]
+where
+    T: Copy + std::ops::Neg<Output = T>,
+{
+    let signed = |direction: Direction, x: T| match direction {
+        Direction::Positive => x,
+        Direction::Negative => -x,
+    };
     let mut b = a;
-    b[to.forward.axis as usize] =
-        (from.forward.direction * to.forward.direction) as i32 as f32 * a[from.forward.axis as usize];
-    b[to.up.axis as usize] = (from.up.direction * to.up.direction) as i32 as f32 * a[from.up.axis as usize];
+    b[to.forward.axis as usize] = signed(
+        from.forward.direction * to.forward.direction,
+        a[from.forward.axis as usize],
+    );
+    b[to.up.axis as usize] = signed(from.up.direction * to.up.direction, a[from.up.axis as usize]);
     b
 }
+
+impl System {
+    /// Convert a point from this co-ordinate system to another.
+    /// See [`transform`].
+    ///
+    /// # Examples
+    ///
+    /// KittyCAD (+Z up, -Y forward) to OpenGL (+Y up, +Z forward):
+    ///
+    /// ```
+    /// # use kittycad_modeling_cmds::{coord::*, shared::Point3d};
+    /// let a = Point3d { x: 1.0, y: 2.0, z: 3.0 };
+    /// let b = KITTYCAD.transform_point(OPENGL, a);
+    /// assert_eq!(b, Point3d { x: 1.0, y: 3.0, z: -2.0 });
+    /// ```
+    pub fn transform_point(&self, to: &System, p: Point3d<f64>) -> Point3d<f64> {
+        transform_components(p.into(), self, to).into()
+    }
+
+    /// Convert a direction vector (e.g. a normal) from this co-ordinate system to another.
+    /// All our co-ordinate systems share the same origin, so this is the same as
+    /// [`System::transform_point`], but it makes the caller's intent clearer.
+    pub fn transform_direction(&self, to: &System, direction: Point3d<f64>) -> Point3d<f64> {
+        self.transform_point(to, direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_point_round_trip() {
+        let systems = [KITTYCAD, OPENGL, VULKAN];
+        let p = Point3d {
+            x: 1.5,
+            y: -2.25,
+            z: 3.0,
+        };
+        for from in systems {
+            for to in systems {
+                let there = from.transform_point(to, p);
+                assert_eq!(to.transform_point(from, there), p, "{from} -> {to} -> {from}");
+                assert_eq!(from.transform_direction(to, p), there);
+            }
+        }
+    }
+
+    #[test]
+    fn transform_point_z_up_to_y_up() {
+        let up_in_kittycad = Point3d { x: 0.0, y: 0.0, z: 1.0 };
+        let up_in_opengl = Point3d { x: 0.0, y: 1.0, z: 0.0 };
+        assert_eq!(KITTYCAD.transform_point(OPENGL, up_in_kittycad), up_in_opengl);
+        assert_eq!(OPENGL.transform_point(KITTYCAD, up_in_opengl), up_in_kittycad);
+    }
+}
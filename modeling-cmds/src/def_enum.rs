@@ -7,7 +7,7 @@ use crate::{self as kittycad_modeling_cmds};
 
 define_modeling_cmd_enum! {
     pub mod each_cmd {
-        use std::collections::HashSet;
+        use std::collections::BTreeSet;
 
         use crate::{self as kittycad_modeling_cmds};
         use kittycad_modeling_cmds_macros::{ModelingCmdVariant};
@@ -17,18 +17,23 @@ define_modeling_cmd_enum! {
         use uuid::Uuid;
 
         use crate::{
+            coord,
             format::OutputFormat,
             id::ModelingCmdId,
             length_unit::LengthUnit,
             shared::{
                 Angle,
                 ComponentTransform,
+                CutStrategy,
                 CutType,
                 CameraMovement,
                 ExtrudedFaceInfo,
-                AnnotationOptions, AnnotationType, CameraDragInteractionType, Color, DistanceType, EntityType,
+                ExtrusionFaceCapType,
+                AnnotationOptions, AnnotationTextAlignmentX, AnnotationType, CameraDragInteractionType, Color,
+                DirectionalLight, DistanceType, EntityType,
                 PathComponentConstraintBound, PathComponentConstraintType, PathSegment, PerspectiveCameraParameters,
-                Point2d, Point3d, SceneSelectionType, SceneToolType,
+                Padding, Point2d, Point3d, PostEffectType, RenderMode, SceneSelectionType, SceneToolType, ToneMapping,
+                WindowCoord,
             },
             units,
         };
@@ -99,6 +104,21 @@ define_modeling_cmd_enum! {
             pub segment: PathSegment,
         }
 
+        /// Extend a path by adding many new segments, in order, as one command.
+        /// The first segment starts at the path's "pen", and each segment after that starts
+        /// where the previous one ended. Either every segment is added, or none are.
+        #[derive(
+            Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct ExtendPathMulti {
+            /// The ID of the command which created the path.
+            pub path: ModelingCmdId,
+            /// Segments to append to the path.
+            pub segments: Vec<PathSegment>,
+        }
+
         /// Command for extruding a solid 2d.
         #[derive(
             Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant,
@@ -133,6 +153,18 @@ define_modeling_cmd_enum! {
             pub sectional: bool,
             /// The maximum acceptable surface gap computed between the revolution surface joints. Must be positive (i.e. greater than zero).
             pub tolerance: LengthUnit,
+            /// How far to rotate the sketch about the trajectory, in total, over the whole sweep.
+            /// The rotation is spread evenly along the trajectory, e.g. for modeling drill bits or augers.
+            /// If `sectional` is true, the twist is still spread over the whole trajectory, not each sub-sweep.
+            /// If not given, the sketch isn't twisted.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub twist_angle: Option<Angle>,
+            /// How much to scale the sketch by at the end of the trajectory, e.g. for tapered pipes.
+            /// The scale is interpolated linearly along the trajectory, starting from 1 (the sketch's original size).
+            /// Must be positive (i.e. greater than zero).
+            /// If not given, the sketch isn't scaled.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub end_scale: Option<f64>,
         }
 
         /// Command for revolving a solid 2d.
@@ -176,6 +208,27 @@ define_modeling_cmd_enum! {
             pub hollow: bool,
         }
 
+        /// Command for thickening a surface (or a face of a solid) into a new solid.
+        /// Unlike shelling, which hollows out an existing solid, this creates a solid from a surface,
+        /// e.g. for sheet-metal-style modeling from imported surfaces.
+        #[derive(
+            Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct ThickenSurface {
+            /// Which surface or face to thicken.
+            pub object_id: Uuid,
+            /// How thick the new solid should be.
+            /// Must be positive (i.e. greater than zero).
+            pub thickness: LengthUnit,
+            /// If true, thicken by `thickness` on both sides of the surface,
+            /// so the solid will be twice as thick.
+            /// If false, only thicken along the surface's normal.
+            #[serde(default)]
+            pub both_sides: bool,
+        }
+
         /// Command for revolving a solid 2d about a brep edge
         #[derive(
             Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant,
@@ -194,6 +247,24 @@ define_modeling_cmd_enum! {
             pub tolerance: LengthUnit,
         }
 
+        /// Command for revolving a solid 2d about a construction axis (see `MakeAxis`)
+        #[derive(
+            Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct RevolveAboutAxis {
+            /// Which sketch to revolve.
+            /// Must be a closed 2D solid.
+            pub target: ModelingCmdId,
+            /// The construction axis to revolve about, must lie in the plane of the solid
+            pub axis_id: Uuid,
+            /// The signed angle of revolution (in degrees, must be <= 360 in either direction)
+            pub angle: Angle,
+            /// The maximum acceptable surface gap computed between the revolution surface joints. Must be positive (i.e. greater than zero).
+            pub tolerance: LengthUnit,
+        }
+
         /// Command for lofting sections to create a solid
         #[derive(
             Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant
@@ -237,7 +308,7 @@ define_modeling_cmd_enum! {
             /// The type of camera drag interaction.
             pub interaction: CameraDragInteractionType,
             /// The initial mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Camera drag continued.
@@ -248,7 +319,7 @@ define_modeling_cmd_enum! {
             /// The type of camera drag interaction.
             pub interaction: CameraDragInteractionType,
             /// The current mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
             /// Logical timestamp. The client should increment this
             /// with every event in the current mouse drag. That way, if the
             /// events are being sent over an unordered channel, the API
@@ -264,7 +335,7 @@ define_modeling_cmd_enum! {
             /// The type of camera drag interaction.
             pub interaction: CameraDragInteractionType,
             /// The final mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Gets the default camera's camera settings
@@ -315,6 +386,32 @@ define_modeling_cmd_enum! {
             pub sequence: Option<u32>,
         }
 
+        /// Change the default camera's near and far clipping planes, without moving the camera.
+        /// Useful for avoiding z-fighting on very large or very small models.
+        /// `z_near` must be less than `z_far`, or the engine responds with an error.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetCameraClipPlanes {
+            /// The distance to the near clipping plane.
+            pub z_near: f32,
+            /// The distance to the far clipping plane.
+            pub z_far: f32,
+        }
+
+        /// Set the default camera's view directly from a 4x4 view matrix,
+        /// e.g. to drive the camera from a VR or AR headset.
+        /// The matrix transforms world coordinates into the camera's coordinates.
+        /// The engine will reject the matrix if it isn't invertible.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct DefaultCameraSetViewMatrix {
+            /// The view matrix, in row-major order,
+            /// i.e. the first 4 elements are the matrix's first row.
+            pub matrix: [f32; 16],
+        }
+
         /// Adjust zoom of the default camera.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -324,6 +421,12 @@ define_modeling_cmd_enum! {
             /// by this magnitudedefaultCameraZoom.
             /// Basically, how much should the camera move forward by.
             pub magnitude: f32,
+            /// If given, the camera won't zoom in closer than this distance from what it's looking at.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub min_distance: Option<f32>,
+            /// If given, the camera won't zoom out further than this distance from what it's looking at.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub max_distance: Option<f32>,
         }
 
         /// Export the scene to a file.
@@ -373,6 +476,10 @@ define_modeling_cmd_enum! {
         pub struct EntityGetAllChildUuids {
             /// ID of the entity being queried.
             pub entity_id: Uuid,
+            /// If given, only return children of these types, e.g. just the faces of a solid.
+            /// If not given, return all children.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub filter: Option<Vec<EntityType>>,
         }
 
         /// What are all UUIDs of all the paths sketched on top of this entity?
@@ -397,6 +504,19 @@ define_modeling_cmd_enum! {
             pub distance_type: DistanceType,
         }
 
+        /// Where are these two entities closest to each other?
+        /// Like `EntityGetDistance`, but also returns the closest point on each entity,
+        /// e.g. for drawing a clearance dimension between them.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct EntityGetClosestPoints {
+            /// ID of the first entity being queried.
+            pub entity_id1: Uuid,
+            /// ID of the second entity being queried.
+            pub entity_id2: Uuid,
+        }
+
         /// Create a pattern using this entity by specifying the transform for each desired repetition.
         /// Transformations are performed in the following order (first applied to last applied): scale, rotate, translate.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
@@ -546,7 +666,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct SelectWithPoint {
             /// Where in the window was selected
-            pub selected_at_window: Point2d,
+            pub selected_at_window: WindowCoord,
             /// What entity was selected?
             pub selection_type: SceneSelectionType,
         }
@@ -569,7 +689,8 @@ define_modeling_cmd_enum! {
             pub entities: Vec<Uuid>,
         }
 
-        /// Removes all of the Objects in the scene
+        /// Removes all of the Objects in the scene.
+        /// This also clears the history used by `Undo` and `Redo`.
         #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
@@ -591,7 +712,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct HighlightSetEntity {
             /// Coordinates of the window being clicked
-            pub selected_at_window: Point2d,
+            pub selected_at_window: WindowCoord,
             /// Logical timestamp. The client should increment this
             /// with every event in the current mouse drag. That way, if the
             /// events are being sent over an unordered channel, the API
@@ -642,6 +763,32 @@ define_modeling_cmd_enum! {
             pub hidden: bool,
         }
 
+        /// Changes the appearance of scene-wide edge lines on brep solids.
+        /// Like `EdgeLinesVisible`, but can also set the lines' color and width,
+        /// e.g. for technical-drawing-style rendering.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetEdgeLineProperties {
+            /// Whether or not the edge lines should be hidden.
+            pub hidden: bool,
+            /// The color of the edge lines. If not given, the color is unchanged.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub color: Option<Color>,
+            /// The width of the edge lines, in pixels. If not given, the width is unchanged.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub width: Option<f32>,
+        }
+
+        /// Set how solids are drawn, e.g. shaded or as a wireframe.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetRenderMode {
+            /// How solids should be drawn.
+            pub mode: RenderMode,
+        }
+
         /// Hide or show an object
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -678,6 +825,23 @@ define_modeling_cmd_enum! {
             /// Ambient Occlusion of the new material
             pub ambient_occlusion: f32,
         }
+
+        /// Set how closely an object's displayed mesh follows its true curves and surfaces,
+        /// e.g. to smooth out an imported solid which looks faceted. Only changes how this
+        /// object is drawn, not how it's exported.
+        /// The object is re-tessellated, so this can be slow for complex objects.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetObjectDisplayTolerance {
+            /// Which object to change.
+            pub object_id: Uuid,
+            /// Furthest the mesh can be from the true surface.
+            pub chord_tolerance: LengthUnit,
+            /// Largest angle between the normals of neighbouring mesh faces.
+            pub angular_tolerance: Angle,
+        }
+
         /// What type of entity is this?
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -772,6 +936,28 @@ define_modeling_cmd_enum! {
             pub face_ids: [Uuid; 2]
         }
 
+        /// Find the curves where two faces meet. The faces can be on different objects.
+        /// Unlike `Solid3dGetCommonEdge`, the faces don't need to share an edge:
+        /// e.g. two cylinders crossing each other meet along a curve through both faces.
+        /// The faces can meet along several separate curves, or none at all.
+        /// Each curve becomes a new path.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SurfaceIntersect {
+            /// The object which the first face belongs to.
+            pub object_id1: Uuid,
+            /// The first face.
+            pub face_id1: Uuid,
+            /// The object which the second face belongs to.
+            pub object_id2: Uuid,
+            /// The second face.
+            pub face_id2: Uuid,
+            /// The maximum acceptable distance between the computed curves and the true intersection.
+            /// Must be positive (i.e. greater than zero).
+            pub tolerance: LengthUnit,
+        }
+
         /// Fillets the given edge with the specified radius.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -794,6 +980,30 @@ define_modeling_cmd_enum! {
             pub face_id: Option<Uuid>,
         }
 
+        /// Chamfers the given edges, cutting them away by the specified distance.
+        /// This is the same cut as `Solid3dFilletEdge` with `CutType::Chamfer`,
+        /// but measured as a distance (and optional angle) rather than a radius.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Solid3dChamferEdge {
+            /// Which object is being chamfered.
+            pub object_id: Uuid,
+            /// Which edges you want to chamfer.
+            pub edge_ids: Vec<Uuid>,
+            /// How far back from the edge to cut, along each adjacent face.
+            /// Measured in length (using the same units that the current sketch uses). Must be positive (i.e. greater than zero).
+            pub distance: LengthUnit,
+            /// The angle between the chamfer and the first adjacent face.
+            /// If given, `distance` is only measured along the first face.
+            /// If not given, the chamfer is symmetric (45 degrees).
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub angle: Option<Angle>,
+            /// How the engine should cut the edges.
+            #[serde(default)]
+            pub strategy: CutStrategy,
+        }
+
         /// Determines whether a brep face is planar and returns its surface-local planar axes if so
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -894,6 +1104,63 @@ define_modeling_cmd_enum! {
             pub hide: Option<bool>,
         }
 
+        /// Make a new plane through three points, e.g. three vertices of a model.
+        /// The plane's origin is `a`, its X axis points from `a` towards `b`,
+        /// and its Y axis is perpendicular to that, on the same side as `c`.
+        /// Responds with an error if the points are all on one line.
+        /// The new plane's ID will be the ID of this command.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakePlaneFromPoints {
+            /// The plane's origin.
+            pub a: Point3d<LengthUnit>,
+            /// A point along the plane's X axis.
+            pub b: Point3d<LengthUnit>,
+            /// Any other point on the plane, which isn't on the line through `a` and `b`.
+            pub c: Point3d<LengthUnit>,
+            /// What should the plane's span/extent?
+            /// When rendered visually, this is both the
+            /// width and height along X and Y axis respectively.
+            pub size: LengthUnit,
+            /// If true, any existing drawables within the obj will be replaced (the object will be reset)
+            pub clobber: bool,
+        }
+
+        /// Make a new plane parallel to an existing one, e.g. for a series of construction planes.
+        /// The new plane has the same axes as the reference plane,
+        /// and its origin is moved along the reference plane's normal.
+        /// The new plane's ID will be the ID of this command.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeOffsetPlane {
+            /// Which plane to offset from.
+            pub reference_plane_id: Uuid,
+            /// How far from the reference plane the new plane should be.
+            /// Positive is in the direction of the reference plane's normal, negative is opposite it.
+            pub offset: LengthUnit,
+            /// What should the plane's span/extent?
+            /// When rendered visually, this is both the
+            /// width and height along X and Y axis respectively.
+            pub size: LengthUnit,
+        }
+
+        /// Make a new construction axis: an infinite line through `origin`, along `direction`.
+        /// Like a plane, it can be referenced by later commands, e.g. as the axis of a revolve.
+        /// The new axis's ID will be the ID of this command.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeAxis {
+            /// Any point on the axis.
+            pub origin: Point3d<LengthUnit>,
+            /// Which way the axis points. Must not be zero.
+            pub direction: Point3d<f64>,
+            /// If true, any existing drawables within the obj will be replaced (the object will be reset)
+            pub clobber: bool,
+        }
+
         /// Set the color of a plane.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -905,6 +1172,18 @@ define_modeling_cmd_enum! {
             pub color: Color,
         }
 
+        /// Set the base color of an entity, keeping the rest of its material.
+        /// Cheaper than setting a whole material with `ObjectSetMaterialParamsPbr`.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct EntitySetColor {
+            /// Which entity is being changed.
+            pub entity_id: Uuid,
+            /// What color it should be.
+            pub color: Color,
+        }
+
         /// Set the current tool.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -920,7 +1199,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct MouseMove {
             /// Where the mouse is
-            pub window: Point2d,
+            pub window: WindowCoord,
             /// Logical timestamp. The client should increment this
             /// with every event in the current mouse drag. That way, if the
             /// events are being sent over an unordered channel, the API
@@ -935,7 +1214,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct MouseClick {
             /// Where the mouse is
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Disable sketch mode.
@@ -1009,6 +1288,47 @@ define_modeling_cmd_enum! {
             pub color: Color,
         }
 
+        /// Set the colors of the scene's grid, and how many minor lines it has.
+        /// The grid is shown if the session was started with `show_grid`.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetGridAppearance {
+            /// The color of the major grid lines.
+            pub major_color: Color,
+            /// The color of the minor grid lines.
+            pub minor_color: Color,
+            /// The number of minor lines between each pair of major lines.
+            pub subdivisions: u32,
+        }
+
+        /// Replace the scene's lighting, e.g. to get better looking snapshots.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetSceneLighting {
+            /// Lights which shine on the whole scene from one direction.
+            pub directional: Vec<DirectionalLight>,
+            /// Light which reaches every surface equally, regardless of direction.
+            /// Its alpha is ignored.
+            pub ambient: Color,
+            /// Whether the directional lights cast shadows.
+            pub shadows: bool,
+        }
+
+        /// Set the camera's exposure and tone mapping, e.g. for snapshots of brightly lit scenes.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetCameraExposure {
+            /// Exposure adjustment, in stops.
+            /// Each stop doubles (if positive) or halves (if negative) the scene's brightness.
+            /// 0 leaves the brightness unchanged.
+            pub exposure: f32,
+            /// How to map the exposed colors onto the screen.
+            pub tone_mapping: ToneMapping,
+        }
+
         /// Set the properties of the tool lines for the scene.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -1095,6 +1415,17 @@ define_modeling_cmd_enum! {
             pub format: ImageFormat,
         }
 
+        /// Take a snapshot of the current view, as uncompressed pixels rather than an encoded image.
+        /// Useful for e.g. ML pipelines which need the raw RGBA buffer (and optionally depth).
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct TakeSnapshotRaw {
+            /// If true, also return the depth buffer.
+            #[serde(default)]
+            pub include_depth: bool,
+        }
+
         /// Add a gizmo showing the axes.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -1149,6 +1480,16 @@ define_modeling_cmd_enum! {
             pub path_id: Uuid,
         }
 
+        /// Obtain the ID and position of each of a path's vertices.
+        /// Like `PathGetVertexUuids`, but saves looking up each vertex's position separately.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct PathGetVertexPositions {
+            /// Which path to query
+            pub path_id: Uuid,
+        }
+
         /// Obtain the sketch target id (if the path was drawn in sketchmode) for a path
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -1164,7 +1505,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct HandleMouseDragStart {
             /// The mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Continue dragging the mouse.
@@ -1173,7 +1514,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct HandleMouseDragMove {
             /// The mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
             /// Logical timestamp. The client should increment this
             /// with every event in the current mouse drag. That way, if the
             /// events are being sent over an unordered channel, the API
@@ -1187,7 +1528,7 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct HandleMouseDragEnd {
             /// The mouse position.
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Remove scene objects.
@@ -1196,7 +1537,8 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct RemoveSceneObjects {
             /// Objects to remove.
-            pub object_ids: HashSet<Uuid>,
+            /// Kept sorted, so the command always serializes the same way.
+            pub object_ids: BTreeSet<Uuid>,
         }
 
         /// Utility method. Performs both a ray cast and projection to plane-local coordinates.
@@ -1208,7 +1550,7 @@ define_modeling_cmd_enum! {
             /// The plane you're intersecting against.
             pub plane_id: Uuid,
             /// Window coordinates where the ray cast should be aimed.
-            pub window: Point2d,
+            pub window: WindowCoord,
         }
 
         /// Find the start and end of a curve.
@@ -1247,6 +1589,48 @@ define_modeling_cmd_enum! {
             pub format: crate::format::InputFormat,
         }
 
+        /// Import a file which the engine downloads itself, e.g. one already in KittyCAD storage.
+        /// Like `ImportFiles`, but saves the client downloading the file and uploading it again.
+        #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct ImportFromUrl {
+            /// Where to download the file from.
+            pub url: String,
+            /// Input file format.
+            pub format: crate::format::InputFormat,
+        }
+
+        /// Which file formats can the engine import and export?
+        /// Useful for listing formats in a UI without hardcoding them.
+        #[derive(
+            Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct GetSupportedFormats {}
+
+        /// Which version of the engine is this, and which optional features does it support?
+        /// Useful for only sending newer commands to engines which support them.
+        #[derive(
+            Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct GetEngineInfo {}
+
+        /// Get every object in the scene, with their faces, edges and other child entities,
+        /// in one response. Useful for tree views, instead of calling `EntityGetAllChildUuids`
+        /// and `GetEntityType` for each entity.
+        /// The response includes every entity in the scene, however deeply nested, so it can be
+        /// very large for big scenes. To explore only part of a big scene, use `EntityGetAllChildUuids`.
+        #[derive(
+            Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant,
+        )]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct GetSceneGraph {}
+
         /// Set the units of the scene.
         /// For all following commands, the units will be interpreted as the given units.
         /// Any previously executed commands will not be affected or have their units changed.
@@ -1259,6 +1643,18 @@ define_modeling_cmd_enum! {
             pub unit: units::UnitLength,
         }
 
+        /// Set which way is up and which way is forward in the scene,
+        /// e.g. +Z up for architecture, or +Y up for games.
+        /// Like `SetSceneUnits`, this only affects commands executed afterwards.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetWorldCoordinateSystem {
+            /// Which co-ordinate system the scene uses.
+            /// Scenes start off using the [`coord::KITTYCAD`] co-ordinate system.
+            pub system: coord::System,
+        }
+
         /// Get the mass of entities in the scene or the default scene.
         #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -1415,11 +1811,9 @@ define_modeling_cmd_enum! {
             #[serde(default = "default_uuid_vector")]
             pub object_ids: Vec<Uuid>,
             /// How much to pad the view frame by, as a fraction of the object(s) bounding box size.
-            /// Negative padding will crop the view of the object proportionally.
-            /// e.g. padding = 0.2 means the view will span 120% of the object(s) bounding box,
-            /// and padding = -0.2 means the view will span 80% of the object(s) bounding box.
+            /// See [`Padding`] for what negative padding does.
             #[serde(default)]
-            pub padding: f32,
+            pub padding: Padding,
             /// Whether or not to animate the camera movement.
             #[serde(default)]
             pub animated: bool,
@@ -1431,11 +1825,9 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct ViewIsometric {
             /// How much to pad the view frame by, as a fraction of the object(s) bounding box size.
-            /// Negative padding will crop the view of the object proportionally.
-            /// e.g. padding = 0.2 means the view will span 120% of the object(s) bounding box,
-            /// and padding = -0.2 means the view will span 80% of the object(s) bounding box.
-            #[serde(default = "f32::default")]
-            pub padding: f32,
+            /// See [`Padding`] for what negative padding does.
+            #[serde(default)]
+            pub padding: Padding,
         }
 
         /// Get a concise description of all of an extrusion's faces.
@@ -1449,6 +1841,42 @@ define_modeling_cmd_enum! {
             pub edge_id: Uuid,
         }
 
+        /// Count a solid's shells, faces, edges and vertices, and check whether it's manifold.
+        /// Useful for validating the result of e.g. a boolean operation.
+        #[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Solid3dGetTopologyInfo {
+            /// The Solid3d object being queried.
+            pub object_id: Uuid,
+        }
+
+        /// Find an extruded solid's faces by their role in the extrusion,
+        /// e.g. "the top face", without having to track the face IDs given to `Extrude`.
+        #[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Solid3dGetFaceByRole {
+            /// The Solid3d object whose faces are being queried.
+            pub object_id: Uuid,
+            /// Which faces to find.
+            /// `Top` or `Bottom` finds that cap, `Both` finds both caps,
+            /// and `None` finds the uncapped side faces.
+            pub role: ExtrusionFaceCapType,
+        }
+
+        /// Find the faces of a solid which were created by a given command, e.g. the new faces
+        /// made by a fillet or a boolean operation.
+        #[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Solid3dGetFacesFromOperation {
+            /// The Solid3d object whose faces are being queried.
+            pub object_id: Uuid,
+            /// The ID of the command which created the faces.
+            pub operation_cmd_id: ModelingCmdId,
+        }
+
         /// Clear the selection
         #[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant)]
         #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -1461,6 +1889,13 @@ define_modeling_cmd_enum! {
         #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
         pub struct SelectGet {}
 
+        /// Find all IDs of selected entities, along with the type of each entity.
+        /// Saves sending a `GetEntityType` for every ID returned by `SelectGet`.
+        #[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SelectGetDetailed {}
+
         /// Get the number of objects in the scene
         #[derive(
             Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Serialize, ModelingCmdVariant,
@@ -1510,6 +1945,244 @@ define_modeling_cmd_enum! {
             /// The distance to offset the path (positive for outset, negative for inset)
             pub offset: LengthUnit,
         }
+
+        /// Does the given entity still exist in the scene?
+        /// Useful for checking whether a cached entity ID is still valid after a
+        /// destructive operation like a boolean or a deletion.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct EntityExists {
+            /// ID of the entity being queried.
+            pub entity_id: Uuid,
+        }
+
+        /// Attach a piece of client metadata to an entity, e.g. its name in KCL.
+        /// The engine stores a string map per entity. Setting a key which already exists
+        /// overwrites its value.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct EntitySetMetadata {
+            /// ID of the entity to attach metadata to.
+            pub entity_id: Uuid,
+            /// Metadata key.
+            pub key: String,
+            /// Metadata value.
+            pub value: String,
+        }
+
+        /// Get client metadata previously attached to an entity with `EntitySetMetadata`.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct EntityGetMetadata {
+            /// ID of the entity being queried.
+            pub entity_id: Uuid,
+            /// Which metadata key to look up.
+            /// If not given, all key-value pairs on the entity are returned.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub key: Option<String>,
+        }
+
+        /// Set which post-processing effect is applied when rendering the scene.
+        /// This reconfigures rendering, so it isn't safe to batch.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetPostEffect {
+            /// Which effect to use.
+            pub effect: PostEffectType,
+        }
+
+        /// Set how many samples per pixel are used for anti-aliasing when rendering the scene.
+        /// This reconfigures rendering, so it isn't safe to batch.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetAntiAliasing {
+            /// Samples per pixel. 1 disables anti-aliasing.
+            pub samples: u8,
+        }
+
+        /// Sketch text onto a plane, as closed paths which can then be extruded (e.g. to emboss or engrave it).
+        /// Each glyph becomes one or more closed paths.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeText {
+            /// The text to sketch.
+            pub text: String,
+            /// Name of the font to use.
+            pub font: String,
+            /// Height of the text (i.e. the font's em size).
+            pub size: LengthUnit,
+            /// Which plane to sketch the text on.
+            pub plane_id: Uuid,
+            /// Where to put the text, in the plane's coordinates.
+            pub position: Point2d<LengthUnit>,
+            /// How the text is aligned horizontally, relative to `position`.
+            pub alignment: AnnotationTextAlignmentX,
+        }
+
+        /// Create a box-shaped solid in one step, without sketching and extruding a rectangle.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeBox {
+            /// Size along the X axis.
+            pub width: LengthUnit,
+            /// Size along the Y axis.
+            pub depth: LengthUnit,
+            /// Size along the Z axis.
+            pub height: LengthUnit,
+            /// Center of the box.
+            pub center: Point3d<LengthUnit>,
+        }
+
+        /// Create a cylindrical solid in one step, without sketching and extruding a circle.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeCylinder {
+            /// Radius of the cylinder.
+            pub radius: LengthUnit,
+            /// Length of the cylinder, along its axis.
+            pub height: LengthUnit,
+            /// Center of the cylinder's base.
+            pub base_center: Point3d<LengthUnit>,
+            /// Direction from the base to the top of the cylinder.
+            pub axis: Point3d<f64>,
+        }
+
+        /// Create a spherical solid in one step.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct MakeSphere {
+            /// Radius of the sphere.
+            pub radius: LengthUnit,
+            /// Center of the sphere.
+            pub center: Point3d<LengthUnit>,
+        }
+
+        /// Draw a line over the scene, e.g. to visualize a computed result like a normal.
+        /// Debug drawings aren't part of the model and can't be selected.
+        /// They stay until `DebugClear` is sent. For persistent drawings, use annotations.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct DebugDrawLine {
+            /// Where the line starts.
+            pub start: Point3d<LengthUnit>,
+            /// Where the line ends.
+            pub end: Point3d<LengthUnit>,
+            /// What color to draw the line.
+            pub color: Color,
+        }
+
+        /// Draw a point over the scene, e.g. to visualize a computed result like a nearest point.
+        /// Debug drawings aren't part of the model and can't be selected.
+        /// They stay until `DebugClear` is sent. For persistent drawings, use annotations.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct DebugDrawPoint {
+            /// Where to draw the point.
+            pub at: Point3d<LengthUnit>,
+            /// What color to draw the point.
+            pub color: Color,
+        }
+
+        /// Remove everything drawn by `DebugDrawLine` and `DebugDrawPoint`.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct DebugClear {}
+
+        /// Start grouping the following commands into one named transaction,
+        /// so they can be undone or rolled back together.
+        /// End the transaction with `CommitTransaction` or `RollbackTransaction`.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct BeginTransaction {
+            /// Describes the transaction, e.g. "Add fillets".
+            pub name: String,
+        }
+
+        /// Finish the transaction started by the last `BeginTransaction`, keeping its changes.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct CommitTransaction {}
+
+        /// Finish the transaction started by the last `BeginTransaction`,
+        /// reverting every change made since then.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct RollbackTransaction {}
+
+        /// Undo the most recent operations.
+        /// A committed transaction (see `BeginTransaction`) counts as one operation.
+        /// `SceneClearAll` clears the history, so nothing before it can be undone.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Undo {
+            /// How many operations to undo.
+            /// If there are fewer operations in the history, undo all of them.
+            pub count: u32,
+        }
+
+        /// Redo operations which were undone with `Undo`.
+        /// Running any other operation after an `Undo` clears what can be redone.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct Redo {
+            /// How many operations to redo.
+            /// If fewer operations were undone, redo all of them.
+            pub count: u32,
+        }
+
+        /// Cut away part of the scene's view with a plane, e.g. for a section view of a solid.
+        /// This only affects rendering: the model itself isn't changed.
+        /// Adds to any clipping planes already set.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SetClippingPlane {
+            /// Which plane to clip with.
+            /// Everything on the side its normal points towards is hidden.
+            pub plane_id: Uuid,
+            /// If true, hide the other side of the plane instead.
+            #[serde(default)]
+            pub flip: bool,
+            /// If true, fill in where the plane cuts through solids, so they don't look hollow.
+            #[serde(default)]
+            pub cap: bool,
+        }
+
+        /// Remove every clipping plane set by `SetClippingPlane`.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct ClearClippingPlanes {}
+
+        /// Intersect a solid with a plane, and make the resulting cross-section into new 2D paths.
+        /// Unlike `SetClippingPlane`, this changes the model: the paths can be dimensioned,
+        /// exported, or used like any other sketch.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ModelingCmdVariant)]
+        #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+        #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+        pub struct SectionToSketch {
+            /// Which solid to take a cross-section of.
+            pub object_id: Uuid,
+            /// Which plane to intersect the solid with.
+            pub plane_id: Uuid,
+        }
     }
 }
 
@@ -1521,9 +2194,11 @@ impl ModelingCmd {
             self,
             MovePathPen(_)
                 | ExtendPath(_)
+                | ExtendPathMulti(_)
                 | Extrude(_)
                 | Revolve(_)
                 | Solid3dFilletEdge(_)
+                | Solid3dChamferEdge(_)
                 | ClosePath(_)
                 | UpdateAnnotation(_)
                 | ObjectVisible(_)
@@ -1532,11 +2207,91 @@ impl ModelingCmd {
                 | SendObject(_)
                 | EntitySetOpacity(_)
                 | PlaneSetColor(_)
+                | EntitySetColor(_)
                 | SetTool(_)
         )
     }
+
+    /// Does this command only read the scene, without changing it?
+    /// These commands are idempotent, so they're safe to send again if they fail.
+    pub fn is_read_only(&self) -> bool {
+        use ModelingCmd::*;
+        matches!(
+            self,
+            DefaultCameraGetSettings(_)
+                | EntityGetParentId(_)
+                | EntityGetNumChildren(_)
+                | EntityGetChildUuid(_)
+                | EntityGetAllChildUuids(_)
+                | EntityGetSketchPaths(_)
+                | EntityGetDistance(_)
+                | EntityGetClosestPoints(_)
+                | EntityExists(_)
+                | EntityGetMetadata(_)
+                | GetEntityType(_)
+                | GetNumObjects(_)
+                | GetSupportedFormats(_)
+                | GetEngineInfo(_)
+                | GetSceneGraph(_)
+                | GetSketchModePlane(_)
+                | SelectGet(_)
+                | SelectGetDetailed(_)
+                | Solid3dGetAllEdgeFaces(_)
+                | Solid3dGetAllOppositeEdges(_)
+                | Solid3dGetOppositeEdge(_)
+                | Solid3dGetNextAdjacentEdge(_)
+                | Solid3dGetPrevAdjacentEdge(_)
+                | Solid3dGetCommonEdge(_)
+                | Solid3dGetExtrusionFaceInfo(_)
+                | Solid3dGetTopologyInfo(_)
+                | Solid3dGetFaceByRole(_)
+                | Solid3dGetFacesFromOperation(_)
+                | FaceIsPlanar(_)
+                | FaceGetPosition(_)
+                | FaceGetCenter(_)
+                | FaceGetGradient(_)
+                | CurveGetType(_)
+                | CurveGetControlPoints(_)
+                | CurveGetEndPoints(_)
+                | PathGetInfo(_)
+                | PathGetCurveUuidsForVertices(_)
+                | PathGetCurveUuid(_)
+                | PathGetVertexUuids(_)
+                | PathGetVertexPositions(_)
+                | PathGetSketchTargetUuid(_)
+                | Mass(_)
+                | Density(_)
+                | Volume(_)
+                | CenterOfMass(_)
+                | SurfaceArea(_)
+        )
+    }
+
+    /// Serialize this command to JSON, in the format the engine expects.
+    /// Useful from JavaScript, where a string is simpler to pass across the boundary than a Rust type.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserialize a command from JSON, e.g. one constructed in JavaScript.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// A key for memoizing or deduplicating this command, e.g. in a `HashMap`.
+    /// Commands with identical fields have equal keys.
+    pub fn cache_key(&self) -> CommandKey {
+        CommandKey(serde_json::to_vec(self).expect("ModelingCmd can always be serialized"))
+    }
 }
 
+/// Identifies a modeling command by its contents. See [`ModelingCmd::cache_key`].
+/// Many commands have float fields, so they can't implement `Eq` or `Hash` themselves.
+/// This key compares their serialized JSON instead, so it's well-behaved even for
+/// NaN fields (which serialize as `null`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CommandKey(Vec<u8>);
+
 /// File to import into the current model.
 /// If you are sending binary data for a file, be sure to send the WebSocketRequest as
 /// binary/bson, not text/json.
@@ -1553,3 +2308,115 @@ pub struct ImportFile {
     )]
     pub data: Vec<u8>,
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{id::ModelingCmdId, length_unit::LengthUnit};
+
+    const ID: Uuid = uuid::uuid!("cc30d5e2-482b-4498-b5d2-6131c30a50a4");
+
+    #[test]
+    fn cache_key() {
+        let extrude = |distance: f64| {
+            ModelingCmd::Extrude(crate::Extrude {
+                target: ModelingCmdId(ID),
+                distance: distance.into(),
+                faces: None,
+            })
+        };
+        assert_eq!(extrude(1.0).cache_key(), extrude(1.0).cache_key());
+        assert_ne!(extrude(1.0).cache_key(), extrude(2.0).cache_key());
+        // NaN isn't equal to itself, but its cache key should be.
+        assert_eq!(extrude(f64::NAN).cache_key(), extrude(f64::NAN).cache_key());
+    }
+
+    #[test]
+    fn padding_is_a_plain_number() {
+        let json = serde_json::json!({"type": "view_isometric", "padding": 0.5});
+        let cmd: ModelingCmd = serde_json::from_value(json.clone()).unwrap();
+        let ModelingCmd::ViewIsometric(view) = &cmd else {
+            panic!("expected ViewIsometric, got {cmd:?}");
+        };
+        assert_eq!(view.padding.get(), 0.5);
+        assert_eq!(serde_json::to_value(&cmd).unwrap(), json);
+    }
+
+    #[test]
+    fn remove_scene_objects_serializes_deterministically() {
+        let ids: Vec<Uuid> = (1..=20).map(Uuid::from_u128).collect();
+        let remove = |ids: Vec<Uuid>| {
+            ModelingCmd::RemoveSceneObjects(crate::RemoveSceneObjects {
+                object_ids: ids.into_iter().collect(),
+            })
+        };
+        let forwards = serde_json::to_string(&remove(ids.clone())).unwrap();
+        let backwards = serde_json::to_string(&remove(ids.iter().rev().copied().collect())).unwrap();
+        assert_eq!(forwards, backwards);
+        assert_eq!(
+            serde_json::to_value(remove(ids.clone())).unwrap(),
+            serde_json::json!({"type": "remove_scene_objects", "object_ids": ids}),
+        );
+    }
+
+    #[test]
+    fn child_uuids_filter_is_optional() {
+        let json = serde_json::json!({"type": "entity_get_all_child_uuids", "entity_id": ID});
+        let cmd: ModelingCmd = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            cmd,
+            ModelingCmd::EntityGetAllChildUuids(crate::EntityGetAllChildUuids {
+                entity_id: ID,
+                filter: None,
+            })
+        );
+        // Without a filter, the command serializes the same as before there was one.
+        assert_eq!(serde_json::to_value(&cmd).unwrap(), json);
+
+        let filtered = ModelingCmd::EntityGetAllChildUuids(crate::EntityGetAllChildUuids {
+            entity_id: ID,
+            filter: Some(vec![crate::shared::EntityType::Face]),
+        });
+        assert_eq!(
+            serde_json::to_value(filtered).unwrap(),
+            serde_json::json!({"type": "entity_get_all_child_uuids", "entity_id": ID, "filter": ["face"]}),
+        );
+    }
+
+    #[test]
+    fn referenced_entity_ids() {
+        let [a, b, c] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let revolve = ModelingCmd::from(crate::RevolveAboutAxis {
+            target: ModelingCmdId(a),
+            axis_id: b,
+            angle: crate::shared::Angle::from_degrees(90.0),
+            tolerance: LengthUnit(0.01),
+        });
+        assert_eq!(revolve.referenced_entity_ids(), vec![a, b]);
+
+        let fillet = ModelingCmd::from(crate::Solid3dFilletEdge {
+            object_id: a,
+            edge_id: b,
+            radius: LengthUnit(1.0),
+            tolerance: LengthUnit(0.01),
+            cut_type: Default::default(),
+            face_id: Some(c),
+        });
+        assert_eq!(fillet.referenced_entity_ids(), vec![a, b, c]);
+
+        let remove = ModelingCmd::from(crate::RemoveSceneObjects { object_ids: [a].into() });
+        assert_eq!(remove.referenced_entity_ids(), vec![a]);
+
+        let common_edge = ModelingCmd::from(crate::Solid3dGetCommonEdge {
+            object_id: a,
+            face_ids: [b, c],
+        });
+        assert_eq!(common_edge.referenced_entity_ids(), vec![a, b, c]);
+
+        assert!(ModelingCmd::from(crate::SelectGet {})
+            .referenced_entity_ids()
+            .is_empty());
+    }
+}
@@ -171,25 +171,70 @@ impl From<OutputFormat> for FileExportFormat {
     }
 }
 
+impl OutputFormat {
+    /// FBX, with the default options.
+    pub fn fbx() -> Self {
+        Self::Fbx(Default::default())
+    }
+
+    /// Binary glTF, i.e. a single .glb file.
+    pub fn gltf_binary() -> Self {
+        Self::Gltf(gltf::export::Options {
+            storage: gltf::export::Storage::Binary,
+            ..Default::default()
+        })
+    }
+
+    /// glTF as a single, pretty-printed .gltf file, with the binary data embedded in it.
+    pub fn gltf_pretty() -> Self {
+        Self::Gltf(gltf::export::Options {
+            storage: gltf::export::Storage::Embedded,
+            presentation: gltf::export::Presentation::Pretty,
+        })
+    }
+
+    /// OBJ, with the default options.
+    pub fn obj() -> Self {
+        Self::Obj(Default::default())
+    }
+
+    /// PLY, with the default options.
+    pub fn ply() -> Self {
+        Self::Ply(Default::default())
+    }
+
+    /// STEP, with the default options.
+    pub fn step() -> Self {
+        Self::Step(Default::default())
+    }
+
+    /// ASCII STL, with the other options at their defaults.
+    pub fn stl_ascii() -> Self {
+        Self::Stl(stl::export::Options {
+            storage: stl::export::Storage::Ascii,
+            ..Default::default()
+        })
+    }
+
+    /// Binary STL, with the other options at their defaults.
+    pub fn stl_binary() -> Self {
+        Self::Stl(stl::export::Options {
+            storage: stl::export::Storage::Binary,
+            ..Default::default()
+        })
+    }
+}
+
 impl From<FileExportFormat> for OutputFormat {
     fn from(export_format: FileExportFormat) -> Self {
         match export_format {
-            FileExportFormat::Fbx => OutputFormat::Fbx(Default::default()),
-            FileExportFormat::Glb => OutputFormat::Gltf(gltf::export::Options {
-                storage: gltf::export::Storage::Binary,
-                ..Default::default()
-            }),
-            FileExportFormat::Gltf => OutputFormat::Gltf(gltf::export::Options {
-                storage: gltf::export::Storage::Embedded,
-                presentation: gltf::export::Presentation::Pretty,
-            }),
-            FileExportFormat::Obj => OutputFormat::Obj(Default::default()),
-            FileExportFormat::Ply => OutputFormat::Ply(Default::default()),
-            FileExportFormat::Step => OutputFormat::Step(Default::default()),
-            FileExportFormat::Stl => OutputFormat::Stl(stl::export::Options {
-                storage: stl::export::Storage::Ascii,
-                ..Default::default()
-            }),
+            FileExportFormat::Fbx => Self::fbx(),
+            FileExportFormat::Glb => Self::gltf_binary(),
+            FileExportFormat::Gltf => Self::gltf_pretty(),
+            FileExportFormat::Obj => Self::obj(),
+            FileExportFormat::Ply => Self::ply(),
+            FileExportFormat::Step => Self::step(),
+            FileExportFormat::Stl => Self::stl_ascii(),
         }
     }
 }
@@ -221,3 +266,63 @@ impl From<FileImportFormat> for InputFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_format_constructors() {
+        let OutputFormat::Gltf(options) = OutputFormat::gltf_binary() else {
+            panic!("expected glTF");
+        };
+        assert_eq!(options.storage, gltf::export::Storage::Binary);
+        assert_eq!(options.presentation, gltf::export::Presentation::default());
+
+        let OutputFormat::Gltf(options) = OutputFormat::gltf_pretty() else {
+            panic!("expected glTF");
+        };
+        assert_eq!(options.storage, gltf::export::Storage::Embedded);
+        assert_eq!(options.presentation, gltf::export::Presentation::Pretty);
+
+        let OutputFormat::Stl(options) = OutputFormat::stl_ascii() else {
+            panic!("expected STL");
+        };
+        assert_eq!(
+            options,
+            stl::export::Options {
+                storage: stl::export::Storage::Ascii,
+                ..Default::default()
+            }
+        );
+
+        let OutputFormat::Stl(options) = OutputFormat::stl_binary() else {
+            panic!("expected STL");
+        };
+        assert_eq!(
+            options,
+            stl::export::Options {
+                storage: stl::export::Storage::Binary,
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(OutputFormat::fbx(), OutputFormat::Fbx(Default::default()));
+        assert_eq!(OutputFormat::obj(), OutputFormat::Obj(Default::default()));
+        assert_eq!(OutputFormat::ply(), OutputFormat::Ply(Default::default()));
+        assert_eq!(OutputFormat::step(), OutputFormat::Step(Default::default()));
+    }
+
+    #[test]
+    fn output_format_round_trips_file_export_format() {
+        for format in enum_iterator::all::<FileExportFormat>() {
+            let output = OutputFormat::from(format);
+            // glb is glTF with binary storage, so it comes back as glTF.
+            let expected = match format {
+                FileExportFormat::Glb => FileExportFormat::Gltf,
+                other => other,
+            };
+            assert_eq!(FileExportFormat::from(output), expected);
+        }
+    }
+}
@@ -45,7 +45,9 @@ pub mod export {
 
     /// Options for exporting PLY.
     #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize, JsonSchema, Display, FromStr)]
-    #[display("coords: {coords}, selection: {selection}, storage: {storage}, units: {units}")]
+    #[display(
+        "coords: {coords}, selection: {selection}, storage: {storage}, include_colors: {include_colors}, units: {units}"
+    )]
     #[serde(rename = "PlyExportOptions")]
     #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
     #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
@@ -63,6 +65,12 @@ pub mod export {
         /// The storage for the output PLY file.
         pub storage: Storage,
 
+        /// Whether to write each vertex's color.
+        ///
+        /// Defaults to false, since some mesh tools can't read PLY files with vertex colors.
+        #[serde(default)]
+        pub include_colors: bool,
+
         /// Export length unit.
         ///
         /// Defaults to meters.
@@ -75,6 +83,7 @@ pub mod export {
                 coords: *coord::KITTYCAD,
                 selection: Default::default(),
                 storage: Default::default(),
+                include_colors: false,
                 units: UnitLength::Meters,
             }
         }
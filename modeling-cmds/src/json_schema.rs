@@ -0,0 +1,79 @@
+use schemars::{
+    gen::SchemaSettings,
+    schema::{InstanceType, Metadata, ObjectValidation, RootSchema, SchemaObject},
+};
+
+use crate::{ok_response::OkModelingCmdResponse, ModelingCmd};
+
+/// One JSON Schema document describing every modeling command and every response.
+/// Useful for generating clients in other languages.
+///
+/// The document is an object with a `command` property (a [`ModelingCmd`]) and a `response`
+/// property (an [`OkModelingCmdResponse`]). Types used by several commands or responses appear
+/// once, under `definitions`, and are referred to with `$ref`s.
+pub fn json_schema_bundle() -> serde_json::Value {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let command = gen.subschema_for::<ModelingCmd>();
+    let response = gen.subschema_for::<OkModelingCmdResponse>();
+    let bundle = RootSchema {
+        meta_schema: gen.settings().meta_schema.clone(),
+        schema: SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some("KittyCAD Modeling API".to_owned()),
+                description: Some("Every modeling command, and every response to them.".to_owned()),
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                properties: [("command".to_owned(), command), ("response".to_owned(), response)]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        definitions: gen.take_definitions(),
+    };
+    serde_json::to_value(bundle).expect("JSON schemas can always be serialized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Find every `$ref` in the schema.
+    fn refs<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("$ref", serde_json::Value::String(r)) => out.push(r),
+                        _ => refs(value, out),
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|value| refs(value, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn bundle_is_valid_schema() {
+        let bundle = json_schema_bundle();
+        // It should be a well-formed schema document.
+        let root: RootSchema = serde_json::from_value(bundle.clone()).unwrap();
+        assert!(root.definitions.contains_key("ModelingCmd"));
+        assert!(root.definitions.contains_key("OkModelingCmdResponse"));
+
+        // Every reference should point at a definition in the same document.
+        let mut all_refs = Vec::new();
+        refs(&bundle, &mut all_refs);
+        assert!(!all_refs.is_empty());
+        for r in all_refs {
+            let name = r
+                .strip_prefix("#/definitions/")
+                .unwrap_or_else(|| panic!("{r} isn't a local definition"));
+            assert!(root.definitions.contains_key(name), "{r} isn't defined");
+        }
+    }
+}
@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 use crate::shared::{Point2d, Point3d, Point4d};
 
 /// A length unit is wrapper around an f64 that represents a length in some unit.
+/// The unit isn't stored: it's whatever unit the scene uses (see the `SetSceneUnits` command),
+/// and the engine converts it to millimeters. So only combine lengths which are in the same unit,
+/// e.g. an offset and a tolerance for the same scene.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
@@ -22,6 +25,23 @@ impl LengthUnit {
     pub fn from_millimeters(&self, to: crate::units::UnitLength) -> LengthUnit {
         LengthUnit(crate::units::UnitLength::Millimeters.convert_to(to, self.0))
     }
+
+    /// Convert this length from the unit it's in (usually the scene's unit) to another unit,
+    /// e.g. to show a measurement in the user's preferred unit.
+    pub fn to_unit(&self, from: crate::units::UnitLength, to: crate::units::UnitLength) -> f64 {
+        LengthUnit(self.to_millimeters(from)).from_millimeters(to).0
+    }
+
+    /// A length in the unit `to` (usually the scene's unit), from a value in the unit `from`,
+    /// e.g. a length the user typed in their preferred unit.
+    pub fn from_unit(value: f64, from: crate::units::UnitLength, to: crate::units::UnitLength) -> Self {
+        LengthUnit(LengthUnit(value).to_millimeters(from)).from_millimeters(to)
+    }
+
+    /// The absolute value of this length, e.g. the size of an offset regardless of its direction.
+    pub fn abs(self) -> Self {
+        LengthUnit(self.0.abs())
+    }
 }
 
 impl Point3d<LengthUnit> {
@@ -145,3 +165,73 @@ impl std::ops::Div<f64> for LengthUnit {
         LengthUnit(self.0 / rhs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = LengthUnit(3.0);
+        let b = LengthUnit(5.0);
+        assert_eq!(a + b, LengthUnit(8.0));
+        assert_eq!(a - b, LengthUnit(-2.0));
+        assert_eq!(a * 2.0, LengthUnit(6.0));
+        assert_eq!(b / 2.0, LengthUnit(2.5));
+        assert_eq!(-a, LengthUnit(-3.0));
+        assert_eq!((a - b).abs(), LengthUnit(2.0));
+        assert!(a < b);
+    }
+
+    #[test]
+    fn unit_round_trips() {
+        use crate::units::UnitLength;
+
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        let inch = LengthUnit::from_unit(1.0, UnitLength::Inches, UnitLength::Millimeters);
+        assert!(close(inch.0, 25.4));
+        assert!(close(inch.to_unit(UnitLength::Millimeters, UnitLength::Inches), 1.0));
+        // The same length in a scene measured in centimeters.
+        let inch = LengthUnit::from_unit(1.0, UnitLength::Inches, UnitLength::Centimeters);
+        assert!(close(inch.0, 2.54));
+        assert!(close(
+            inch.to_unit(UnitLength::Centimeters, UnitLength::Millimeters),
+            25.4
+        ));
+
+        let units = [
+            UnitLength::Millimeters,
+            UnitLength::Inches,
+            UnitLength::Feet,
+            UnitLength::Centimeters,
+            UnitLength::Meters,
+        ];
+        for value in [0.0, 1.0, 12.5, -3.75, 1000.0] {
+            for scene_unit in units {
+                for unit in units {
+                    let length = LengthUnit::from_unit(value, unit, scene_unit);
+                    assert!(
+                        close(length.to_unit(scene_unit, unit), value),
+                        "{value} {unit} didn't round trip through {scene_unit}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tolerance_check() {
+        // Whether two lengths are within a tolerance of each other, without unwrapping them.
+        let within = |a: LengthUnit, b: LengthUnit, tolerance: LengthUnit| (a - b).abs() <= tolerance;
+        let tolerance = LengthUnit(0.01);
+        assert!(within(LengthUnit(10.0), LengthUnit(10.005), tolerance));
+        assert!(within(LengthUnit(10.005), LengthUnit(10.0), tolerance));
+        assert!(!within(LengthUnit(10.0), LengthUnit(10.02), tolerance));
+        // Scaling both lengths and the tolerance together doesn't change the answer.
+        assert!(within(
+            LengthUnit(10.0) * 10.0,
+            LengthUnit(10.005) * 10.0,
+            tolerance * 10.0
+        ));
+    }
+}
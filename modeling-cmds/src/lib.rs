@@ -2,6 +2,19 @@
 //! # Beware
 //! This project does not use semver. We are using 0.1.x for everything. If you use this crate, commit your Cargo.lock to avoid being broken when we publish a new version.
 //! Why? Because we use this primarily for KittyCAD server and clients, where we are on top of all changes.
+//! # Features
+//! With no features enabled, this crate only contains the modeling commands, their responses,
+//! and the geometry types they use. It has no async runtime or networking dependencies,
+//! and builds for `wasm32-unknown-unknown` without extra setup (`uuid` is built with its `js` feature,
+//! for random IDs). `ModelingCmd::to_json` and `from_json` help pass commands to and from JavaScript.
+//! - `websocket`: types for the WebSocket API, used to send commands to the engine.
+//! - `webrtc`: conversions between our WebRTC types and the `webrtc` crate's. Implies `websocket`.
+//! - `derive-jsonschema-on-enums`: JSON schemas for `ModelingCmd` and other generated enums,
+//!   and `json_schema_bundle` to get one schema for every command and response.
+//! - `convert_client_crate`: conversions to and from types in the `kittycad` client crate.
+//! - `test-util`: helpers for testing that commands serialize to the JSON you expect,
+//!   and an example of every command.
+//! - `ts-rs`, `tabled`, `slog`, `cxx`: derive or implement traits from those crates.
 
 pub mod base64;
 #[cfg(feature = "convert_client_crate")]
@@ -19,6 +32,8 @@ pub mod format;
 pub mod id;
 #[cfg(feature = "cxx")]
 pub mod impl_extern_type;
+#[cfg(feature = "derive-jsonschema-on-enums")]
+mod json_schema;
 pub mod length_unit;
 /// When a modeling command is successful, these responses could be returned.
 pub mod ok_response;
@@ -26,6 +41,8 @@ pub mod ok_response;
 pub mod session;
 /// Types that are shared between various modeling commands, like Point3d.
 pub mod shared;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 /// The modeling command trait that each modeling command implements.
 mod traits;
 /// Units of measurement.
@@ -35,5 +52,7 @@ pub mod units;
 pub mod websocket;
 
 pub use def_enum::*;
+#[cfg(feature = "derive-jsonschema-on-enums")]
+pub use json_schema::json_schema_bundle;
 pub use ok_response::output;
 pub use traits::*;
@@ -7,6 +7,8 @@ define_ok_modeling_cmd_response_enum! {
     /// Output from Modeling API commands.
     pub mod output {
 
+        use std::collections::BTreeMap;
+
         use kittycad_modeling_cmds_macros::ModelingCmdOutput;
         use schemars::JsonSchema;
         use serde::{Deserialize, Serialize};
@@ -18,7 +20,10 @@ define_ok_modeling_cmd_response_enum! {
             base64::Base64Data,
             id::ModelingCmdId,
             length_unit::LengthUnit,
-            shared::{CurveType, EntityType, ExportFile, ExtrusionFaceCapType, PathCommand, Point2d, Point3d},
+            shared::{
+                CurveType, EntityType, ExportFile, ExtrusionFaceCapType, FileExportFormat, FileImportFormat,
+                PathCommand, PathVertex, Point2d, Point3d, SceneGraphNode, SelectedEntity,
+            },
             units,
         };
 
@@ -44,6 +49,11 @@ define_ok_modeling_cmd_response_enum! {
         pub struct ExtendPath {
         }
 
+        /// The response from the `ExtendPathMulti` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct ExtendPathMulti {
+        }
+
         /// The response from the `Extrude` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct Extrude {
@@ -64,11 +74,23 @@ define_ok_modeling_cmd_response_enum! {
         pub struct Solid3dShellFace {
         }
 
+        /// The response from the `ThickenSurface` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct ThickenSurface {
+            /// The UUID of the new solid.
+            pub solid_id: Uuid,
+        }
+
         /// The response from the `RevolveAboutEdge` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct RevolveAboutEdge {
         }
 
+        /// The response from the `RevolveAboutAxis` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct RevolveAboutAxis {
+        }
+
         /// The response from the `CameraDragStart` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct CameraDragStart {
@@ -84,6 +106,11 @@ define_ok_modeling_cmd_response_enum! {
         pub struct DefaultCameraPerspectiveSettings {
         }
 
+        /// The response from the `SetCameraClipPlanes` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetCameraClipPlanes {
+        }
+
         /// The response from the `SelectAdd` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct SelectAdd {
@@ -123,6 +150,16 @@ define_ok_modeling_cmd_response_enum! {
         pub struct EdgeLinesVisible {
         }
 
+        /// The response from the `SetEdgeLineProperties` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetEdgeLineProperties {
+        }
+
+        /// The response from the `SetRenderMode` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetRenderMode {
+        }
+
         /// The response from the `ObjectVisible` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct ObjectVisible {
@@ -138,6 +175,11 @@ define_ok_modeling_cmd_response_enum! {
         pub struct ObjectSetMaterialParamsPbr {
         }
 
+        /// The response from the `SetObjectDisplayTolerance` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetObjectDisplayTolerance {
+        }
+
         /// The response from the `Solid2dAddHole` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct Solid2dAddHole {
@@ -148,6 +190,11 @@ define_ok_modeling_cmd_response_enum! {
         pub struct Solid3dFilletEdge {
         }
 
+        /// The response from the `Solid3dChamferEdge` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Solid3dChamferEdge {
+        }
+
         /// The response from the `SendObject` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct SendObject {
@@ -168,11 +215,31 @@ define_ok_modeling_cmd_response_enum! {
         pub struct MakePlane {
         }
 
+        /// The response from the `MakePlaneFromPoints` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakePlaneFromPoints {
+        }
+
+        /// The response from the `MakeOffsetPlane` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeOffsetPlane {
+        }
+
+        /// The response from the `MakeAxis` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeAxis {
+        }
+
         /// The response from the `PlaneSetColor` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct PlaneSetColor {
         }
 
+        /// The response from the `EntitySetColor` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct EntitySetColor {
+        }
+
         /// The response from the `SetTool` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct SetTool {
@@ -213,6 +280,21 @@ define_ok_modeling_cmd_response_enum! {
         pub struct SetBackgroundColor {
         }
 
+        /// The response from the `SetGridAppearance` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetGridAppearance {
+        }
+
+        /// The response from the `SetSceneLighting` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetSceneLighting {
+        }
+
+        /// The response from the `SetCameraExposure` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetCameraExposure {
+        }
+
         /// The response from the `SetCurrentToolProperties` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct SetCurrentToolProperties {
@@ -258,6 +340,11 @@ define_ok_modeling_cmd_response_enum! {
         pub struct SetSceneUnits {
         }
 
+        /// The response from the `SetWorldCoordinateSystem` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetWorldCoordinateSystem {
+        }
+
         /// The response from the `SetSelectionType` endpoint.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct SetSelectionType {
@@ -382,6 +469,13 @@ define_ok_modeling_cmd_response_enum! {
             pub settings: CameraSettings
         }
 
+        /// The response from the `DefaultCameraSetViewMatrix` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct DefaultCameraSetViewMatrix {
+            /// Camera settings
+            pub settings: CameraSettings
+        }
+
         /// The response from the `DefaultCameraZoom` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct DefaultCameraZoom {
@@ -445,6 +539,13 @@ define_ok_modeling_cmd_response_enum! {
             pub entity_ids: Vec<Uuid>,
         }
 
+        /// The response from the `SelectGetDetailed` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SelectGetDetailed {
+            /// The selected entities, and their types.
+            pub entities: Vec<SelectedEntity>,
+        }
+
         /// The response from the `Solid3dGetAllEdgeFaces` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct Solid3dGetAllEdgeFaces {
@@ -487,6 +588,14 @@ define_ok_modeling_cmd_response_enum! {
             pub edge: Option<Uuid>,
         }
 
+        /// The response from the `SurfaceIntersect` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SurfaceIntersect {
+            /// The UUIDs of the new paths, one for each curve where the faces meet.
+            /// Empty if the faces don't meet.
+            pub path_ids: Vec<Uuid>,
+        }
+
         /// The response from the `GetEntityType` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct GetEntityType {
@@ -537,6 +646,24 @@ define_ok_modeling_cmd_response_enum! {
             pub contents: Base64Data,
         }
 
+        /// The response from the `TakeSnapshotRaw` command.
+        /// Pixels are stored row-major, starting from the top-left corner of the view.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct TakeSnapshotRaw {
+            /// Width of the image, in pixels.
+            pub width: u32,
+            /// Height of the image, in pixels.
+            pub height: u32,
+            /// Color of each pixel, as 4 bytes: red, green, blue, alpha.
+            /// So this is `width * height * 4` bytes long.
+            pub rgba: Base64Data,
+            /// Depth of each pixel, as a little-endian `f32` (4 bytes), normalized to 0 (near plane) to 1 (far plane).
+            /// So this is `width * height * 4` bytes long.
+            /// Only present if `include_depth` was set.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub depth: Option<Base64Data>,
+        }
+
         /// The response from the `PathGetInfo` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct PathGetInfo {
@@ -578,6 +705,13 @@ define_ok_modeling_cmd_response_enum! {
             pub vertex_ids: Vec<Uuid>,
         }
 
+        /// The response from the `PathGetVertexPositions` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct PathGetVertexPositions {
+            /// The ID and position of each of the path's vertices.
+            pub vertices: Vec<PathVertex>,
+        }
+
         /// The response from the `PathGetSketchTargetUuid` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct PathGetSketchTargetUuid {
@@ -651,6 +785,38 @@ define_ok_modeling_cmd_response_enum! {
             pub object_id: Uuid,
         }
 
+        /// The response from the `ImportFromUrl` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct ImportFromUrl {
+            /// ID of the imported 3D models within the scene.
+            pub object_id: Uuid,
+        }
+
+        /// The response from the `GetSupportedFormats` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct GetSupportedFormats {
+            /// Formats the engine can import.
+            pub import: Vec<FileImportFormat>,
+            /// Formats the engine can export.
+            pub export: Vec<FileExportFormat>,
+        }
+
+        /// The response from the `GetEngineInfo` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct GetEngineInfo {
+            /// The engine's version.
+            pub version: String,
+            /// Names of the optional features this engine supports.
+            pub capabilities: Vec<String>,
+        }
+
+        /// The response from the `GetSceneGraph` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct GetSceneGraph {
+            /// Each object in the scene, with the entities within it.
+            pub objects: Vec<SceneGraphNode>,
+        }
+
         /// Data from importing the files
         #[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct ImportedGeometry {
@@ -732,6 +898,17 @@ define_ok_modeling_cmd_response_enum! {
             pub max_distance: LengthUnit,
         }
 
+        /// The response from the `EntityGetClosestPoints` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct EntityGetClosestPoints {
+            /// The point on the first entity which is closest to the second entity.
+            pub point1: Point3d<LengthUnit>,
+            /// The point on the second entity which is closest to the first entity.
+            pub point2: Point3d<LengthUnit>,
+            /// The distance between the two points, i.e. the minimum distance between the entities.
+            pub distance: LengthUnit,
+        }
+
         /// The response from the `EntityLinearPatternTransform` command.
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct EntityLinearPatternTransform {
@@ -789,6 +966,37 @@ define_ok_modeling_cmd_response_enum! {
             pub faces: Vec<ExtrusionFaceInfo>,
         }
 
+        /// The response from the `Solid3dGetTopologyInfo` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Solid3dGetTopologyInfo {
+            /// How many shells the solid has.
+            /// A solid with an internal void has more than one.
+            pub num_shells: u32,
+            /// How many faces the solid has.
+            pub num_faces: u32,
+            /// How many edges the solid has.
+            pub num_edges: u32,
+            /// How many vertices the solid has.
+            pub num_vertices: u32,
+            /// True if every edge is shared by exactly two faces,
+            /// i.e. the solid is watertight and has no non-manifold edges.
+            pub is_manifold: bool,
+        }
+
+        /// The response from the `Solid3dGetFaceByRole` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Solid3dGetFaceByRole {
+            /// The UUIDs of the faces with the requested role.
+            pub face_ids: Vec<Uuid>,
+        }
+
+        /// The response from the `Solid3dGetFacesFromOperation` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Solid3dGetFacesFromOperation {
+            /// The UUIDs of the faces the operation created.
+            pub face_ids: Vec<Uuid>,
+        }
+
         /// Extrusion face info struct (useful for maintaining mappings between source path segment ids and extrusion faces)
         #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
         pub struct ExtrusionFaceInfo {
@@ -803,5 +1011,151 @@ define_ok_modeling_cmd_response_enum! {
             pub cap: ExtrusionFaceCapType,
         }
 
+        /// The response from the `EntityExists` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct EntityExists {
+            /// True if the entity exists, false if it was never created or has since been deleted.
+            pub exists: bool,
+        }
+
+        /// The response from the `EntitySetMetadata` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct EntitySetMetadata {
+        }
+
+        /// The response from the `EntityGetMetadata` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct EntityGetMetadata {
+            /// The requested key-value pairs.
+            /// If a key was requested, this has at most one entry (none if the key isn't set).
+            pub metadata: BTreeMap<String, String>,
+        }
+
+        /// The response from the `SetPostEffect` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetPostEffect {
+        }
+
+        /// The response from the `SetAntiAliasing` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetAntiAliasing {
+        }
+
+        /// The response from the `MakeText` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeText {
+            /// IDs of the closed paths making up the text's glyphs, in the order the glyphs appear in the text.
+            pub path_ids: Vec<Uuid>,
+        }
+
+        /// The response from the `MakeBox` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeBox {
+            /// The UUID of the new solid.
+            pub object_id: Uuid,
+        }
+
+        /// The response from the `MakeCylinder` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeCylinder {
+            /// The UUID of the new solid.
+            pub object_id: Uuid,
+        }
+
+        /// The response from the `MakeSphere` command.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct MakeSphere {
+            /// The UUID of the new solid.
+            pub object_id: Uuid,
+        }
+
+        /// The response from the `DebugDrawLine` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct DebugDrawLine {
+        }
+
+        /// The response from the `DebugDrawPoint` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct DebugDrawPoint {
+        }
+
+        /// The response from the `DebugClear` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct DebugClear {
+        }
+
+        /// The response from the `BeginTransaction` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct BeginTransaction {
+        }
+
+        /// The response from the `CommitTransaction` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct CommitTransaction {
+        }
+
+        /// The response from the `RollbackTransaction` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct RollbackTransaction {
+        }
+
+        /// The response from the `Undo` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Undo {
+        }
+
+        /// The response from the `Redo` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct Redo {
+        }
+
+        /// The response from the `SetClippingPlane` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SetClippingPlane {
+        }
+
+        /// The response from the `ClearClippingPlanes` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct ClearClippingPlanes {
+        }
+
+        /// The response from the `SectionToSketch` endpoint.
+        #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, ModelingCmdOutput)]
+        pub struct SectionToSketch {
+            /// The UUIDs of the new paths, one for each closed loop in the cross-section.
+            /// Empty if the plane doesn't intersect the solid.
+            pub path_ids: Vec<Uuid>,
+        }
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_response_is_kept() {
+        let json = serde_json::json!({
+            "type": "some_response_from_the_future",
+            "data": {"answer": 42}
+        });
+        let resp: OkModelingCmdResponse = serde_json::from_value(json.clone()).unwrap();
+        let OkModelingCmdResponse::Unknown(unknown) = &resp else {
+            panic!("expected an unknown response, got {resp:?}");
+        };
+        assert_eq!(unknown.response_type, "some_response_from_the_future");
+        assert_eq!(unknown.data, serde_json::json!({"answer": 42}));
+        assert_eq!(resp.to_string(), "Unknown(some_response_from_the_future)");
+        // It serializes back to what the engine sent.
+        assert_eq!(serde_json::to_value(&resp).unwrap(), json);
+
+        // Known responses are unaffected.
+        let empty: OkModelingCmdResponse = serde_json::from_value(serde_json::json!({"type": "empty"})).unwrap();
+        assert!(matches!(empty, OkModelingCmdResponse::Empty));
+
+        // A known response with invalid data is still an error, rather than an unknown response.
+        let invalid = serde_json::json!({"type": "select_get", "data": {"entity_ids": "not a list"}});
+        assert!(serde_json::from_value::<OkModelingCmdResponse>(invalid).is_err());
     }
 }
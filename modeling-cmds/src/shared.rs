@@ -25,6 +25,21 @@ pub enum CutType {
     Chamfer,
 }
 
+/// How the engine should go about cutting edges.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub enum CutStrategy {
+    /// Cut each edge directly. Fast, but fails for some geometry, e.g. cut edges which touch each other.
+    Basic,
+    /// Cut using constructive solid geometry. Slower, but works for more geometry.
+    Csg,
+    /// Try the basic strategy, and if that fails, fall back to CSG.
+    #[default]
+    Automatic,
+}
+
 /// A rotation defined by an axis, origin of rotation, and an angle.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -182,6 +197,110 @@ pub struct Color {
     pub a: f32,
 }
 
+impl Color {
+    /// Opaque white.
+    pub const WHITE: Self = Self::rgb(1.0, 1.0, 1.0);
+    /// Opaque black.
+    pub const BLACK: Self = Self::rgb(0.0, 0.0, 0.0);
+    /// Opaque red.
+    pub const RED: Self = Self::rgb(1.0, 0.0, 0.0);
+    /// Opaque green.
+    pub const GREEN: Self = Self::rgb(0.0, 1.0, 0.0);
+    /// Opaque blue.
+    pub const BLUE: Self = Self::rgb(0.0, 0.0, 1.0);
+    /// Fully transparent (black, but you can't see it).
+    pub const TRANSPARENT: Self = Self::BLACK.with_alpha(0.0);
+
+    /// Make an opaque color from red, green and blue channels, each from 0 to 1.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// The same color, but with the given alpha (0 is transparent, 1 is opaque).
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Make a color from 8-bit channels, where 0 is none and 255 is full intensity.
+    pub const fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: a as f32 / 255.0,
+        }
+    }
+
+    /// Parse a CSS-style hex color: `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`.
+    /// The leading `#` is optional. If alpha isn't given, the color is opaque.
+    pub fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        // Check every character first, because `from_str_radix` accepts a leading `+`,
+        // and slicing needs every character to be one byte long.
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ColorParseError::InvalidDigit);
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map_err(|_| ColorParseError::InvalidDigit);
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| ColorParseError::InvalidDigit);
+        // In the short forms, each digit is repeated, i.e. `#f80` means `#ff8800`.
+        let short = |i: usize| digit(i).map(|d| d * 17);
+        match hex.len() {
+            3 => Ok(Self::rgba8(short(0)?, short(1)?, short(2)?, 255)),
+            4 => Ok(Self::rgba8(short(0)?, short(1)?, short(2)?, short(3)?)),
+            6 => Ok(Self::rgba8(byte(0)?, byte(2)?, byte(4)?, 255)),
+            8 => Ok(Self::rgba8(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            len => Err(ColorParseError::InvalidLength(len)),
+        }
+    }
+
+    /// Format as a hex color, `#rrggbbaa`.
+    /// Channels outside the 0 to 1 range are clamped.
+    pub fn to_hex(&self) -> String {
+        let byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            byte(self.r),
+            byte(self.g),
+            byte(self.b),
+            byte(self.a)
+        )
+    }
+}
+
+/// Error returned when a string isn't a valid hex color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// Hex colors must have 3, 4, 6 or 8 digits, but this one had a different number.
+    InvalidLength(usize),
+    /// A character wasn't a hex digit.
+    InvalidDigit,
+}
+
+impl std::fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(f, "hex colors must have 3, 4, 6 or 8 digits, but found {len}"),
+            Self::InvalidDigit => write!(f, "hex colors can only contain the digits 0-9 and a-f"),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// A light infinitely far away, which lights the whole scene from one direction, like the sun.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct DirectionalLight {
+    /// Which way the light travels, i.e. pointing from the light towards the scene.
+    /// Doesn't need to be normalized.
+    pub direction: Point3d<f64>,
+    /// The color of the light.
+    pub color: Color,
+    /// How bright the light is. 1 is the brightness of the default lighting.
+    pub intensity: f32,
+}
+
 /// Horizontal Text alignment
 #[allow(missing_docs)]
 #[derive(
@@ -326,6 +445,125 @@ pub enum PathSegment {
     },
 }
 
+impl PathSegment {
+    /// If this is a [`PathSegment::Bezier`], approximate it with a polyline, e.g. for previews or hit-testing.
+    /// Every point on the curve is within `tolerance` of the polyline.
+    /// The polyline starts at `start` (the pen position the segment starts from) and ends at the curve's end.
+    /// A straight curve gives just those two points; curvier ones are subdivided into more.
+    /// Returns `None` for other kinds of segment.
+    pub fn subdivide(&self, start: Point3d<LengthUnit>, tolerance: LengthUnit) -> Option<Vec<Point3d<LengthUnit>>> {
+        let PathSegment::Bezier {
+            control1,
+            control2,
+            end,
+            relative,
+        } = *self
+        else {
+            return None;
+        };
+        let start = start.map(|l| l.0);
+        let offset = if relative { start } else { Point3d::default() };
+        let to_abs = |p: Point3d<LengthUnit>| p.map(|l| l.0) + offset;
+        let curve = [start, to_abs(control1), to_abs(control2), to_abs(end)];
+        let mut points = vec![start];
+        flatten_cubic(curve, tolerance.0.abs(), 0, &mut points);
+        Some(points.into_iter().map(|p| p.map(LengthUnit)).collect())
+    }
+
+    /// If this is a [`PathSegment::Arc`], approximate it with cubic [`PathSegment::Bezier`]s,
+    /// e.g. for exporting to formats which don't support true arcs.
+    /// The arc is split into pieces of at most 90 degrees, each of which becomes one bezier.
+    /// Each bezier's distance from the true circle is at most 0.03% of the radius.
+    ///
+    /// Like the arc, the beziers begin at the arc's start point, so the pen should be there.
+    /// If the arc is relative, so are the beziers (each one is relative to where the previous one ended).
+    /// Returns `None` for other kinds of segment.
+    pub fn to_beziers(&self) -> Option<Vec<PathSegment>> {
+        let PathSegment::Arc {
+            center,
+            radius,
+            start,
+            end,
+            relative,
+        } = *self
+        else {
+            return None;
+        };
+        let (center, radius) = (center.map(|l| l.0), radius.0);
+        let start = start.to_radians();
+        let sweep = end.to_radians() - start;
+        let num_pieces = (sweep.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0);
+        let piece_sweep = sweep / num_pieces;
+        // Distance from each end of a piece to the nearest control point, as a fraction of the radius.
+        let k = 4.0 / 3.0 * (piece_sweep / 4.0).tan();
+        let on_circle = |angle: f64| Point2d {
+            x: center.x + radius * angle.cos(),
+            y: center.y + radius * angle.sin(),
+        };
+        let tangent = |angle: f64| Point2d {
+            x: -radius * angle.sin(),
+            y: radius * angle.cos(),
+        };
+
+        let beziers = (0..num_pieces as u32)
+            .map(|i| {
+                let a0 = start + piece_sweep * f64::from(i);
+                let a1 = a0 + piece_sweep;
+                let p0 = on_circle(a0);
+                let p3 = on_circle(a1);
+                let p1 = p0 + tangent(a0) * k;
+                let p2 = p3 - tangent(a1) * k;
+                let offset = if relative { p0 } else { Point2d::zero() };
+                let to_segment_point = |p: Point2d<f64>| (p - offset).with_z(0.0).map(LengthUnit);
+                PathSegment::Bezier {
+                    control1: to_segment_point(p1),
+                    control2: to_segment_point(p2),
+                    end: to_segment_point(p3),
+                    relative,
+                }
+            })
+            .collect();
+        Some(beziers)
+    }
+}
+
+/// Append points approximating the given cubic bezier (excluding its start point) to `out`.
+fn flatten_cubic(curve: [Point3d<f64>; 4], tolerance: f64, depth: u32, out: &mut Vec<Point3d<f64>>) {
+    // Stop subdividing eventually, even if the tolerance is zero or the curve is degenerate.
+    const MAX_DEPTH: u32 = 16;
+    let [p0, p1, p2, p3] = curve;
+    // The curve lies inside the convex hull of its control points. So if the inner control points
+    // are within tolerance of the chord, the whole curve is too.
+    let flat = distance_to_segment(p1, p0, p3) <= tolerance && distance_to_segment(p2, p0, p3) <= tolerance;
+    if flat || depth >= MAX_DEPTH {
+        out.push(p3);
+        return;
+    }
+    // Split in half with de Casteljau's algorithm.
+    let mid = |a: Point3d<f64>, b: Point3d<f64>| (a + b) * 0.5;
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let halfway = mid(p012, p123);
+    flatten_cubic([p0, p01, p012, halfway], tolerance, depth + 1, out);
+    flatten_cubic([halfway, p123, p23, p3], tolerance, depth + 1, out);
+}
+
+/// Shortest distance from point `p` to the line segment from `a` to `b`.
+fn distance_to_segment(p: Point3d<f64>, a: Point3d<f64>, b: Point3d<f64>) -> f64 {
+    let dot = |u: Point3d<f64>, v: Point3d<f64>| u.x * v.x + u.y * v.y + u.z * v.z;
+    let ab = b - a;
+    let ap = p - a;
+    let len_sq = dot(ab, ab);
+    let t = if len_sq == 0.0 {
+        0.0
+    } else {
+        (dot(ap, ab) / len_sq).clamp(0.0, 1.0)
+    };
+    let closest = a + ab * t;
+    let d = p - closest;
+    dot(d, d).sqrt()
+}
+
 /// An angle, with a specific unit.
 #[derive(Clone, Copy, PartialEq, Debug, JsonSchema, Deserialize, Serialize)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -558,6 +796,41 @@ pub enum EntityType {
     Vertex,
 }
 
+/// A selected entity, and what type of entity it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct SelectedEntity {
+    /// The UUID of the selected entity.
+    pub entity_id: Uuid,
+    /// The type of the selected entity.
+    pub entity_type: EntityType,
+}
+
+/// A vertex of a path, and where it is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct PathVertex {
+    /// The UUID of the vertex entity.
+    pub vertex_id: Uuid,
+    /// Where the vertex is, in world coordinates.
+    pub position: Point3d<LengthUnit>,
+}
+
+/// An entity in the scene, and the entities within it (e.g. an object's faces and edges).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct SceneGraphNode {
+    /// The UUID of the entity.
+    pub entity_id: Uuid,
+    /// The type of the entity.
+    pub entity_type: EntityType,
+    /// The entities within this one.
+    pub children: Vec<SceneGraphNode>,
+}
+
 /// The type of Curve (embedded within path)
 #[allow(missing_docs)]
 #[derive(
@@ -799,6 +1072,84 @@ pub enum CameraMovement {
     None,
 }
 
+/// How much to pad the camera's view of some objects, as a fraction of their bounding box size.
+/// Positive padding leaves space around the objects,
+/// e.g. 0.2 means the view will span 120% of the bounding box.
+/// Negative padding crops the view of the objects proportionally,
+/// e.g. -0.2 means the view will span 80% of the bounding box.
+/// Zero (the default) fits the bounding box exactly.
+///
+/// Serialized as a plain number. Deserializing checks the number like [`Padding::new`] does.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "f32", into = "f32")]
+#[schemars(transparent)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct Padding(f32);
+
+impl Padding {
+    /// Fit the objects' bounding box exactly.
+    pub const NONE: Self = Self(0.0);
+
+    /// Pad by the given fraction of the bounding box size.
+    /// Returns `None` if the fraction isn't finite, or if it's -1 or less,
+    /// because that would crop the view down to nothing.
+    pub fn new(fraction: f32) -> Option<Self> {
+        (fraction.is_finite() && fraction > -1.0).then_some(Self(fraction))
+    }
+
+    /// The padding, as a fraction of the bounding box size.
+    pub const fn get(self) -> f32 {
+        self.0
+    }
+}
+
+impl From<Padding> for f32 {
+    fn from(padding: Padding) -> Self {
+        padding.0
+    }
+}
+
+impl TryFrom<f32> for Padding {
+    type Error = InvalidPadding;
+
+    fn try_from(fraction: f32) -> Result<Self, Self::Error> {
+        Self::new(fraction).ok_or(InvalidPadding(fraction))
+    }
+}
+
+/// Error returned when a number can't be used as [`Padding`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidPadding(pub f32);
+
+impl std::fmt::Display for InvalidPadding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "padding must be a finite number greater than -1, but found {}",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidPadding {}
+
+/// A position in the window showing the scene, in pixels.
+/// Kept separate from [`Point2d`]s in model space (e.g. in a sketch), so the two can't be mixed up.
+///
+/// Serialized the same as a plain [`Point2d`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub struct WindowCoord(pub Point2d);
+
+impl From<Point2d> for WindowCoord {
+    fn from(point: Point2d) -> Self {
+        Self(point)
+    }
+}
+
 /// The global axes.
 #[derive(
     Display, FromStr, Copy, Eq, PartialEq, Debug, JsonSchema, Deserialize, Serialize, Sequence, Clone, Ord, PartialOrd,
@@ -862,6 +1213,42 @@ pub enum PostEffectType {
     NoEffect,
 }
 
+/// How to map the scene's high dynamic range colors into the range a screen can show.
+#[derive(
+    Display, FromStr, Copy, Eq, PartialEq, Debug, JsonSchema, Deserialize, Serialize, Sequence, Clone, Ord, PartialOrd,
+)]
+#[serde(rename_all = "snake_case")]
+#[display(style = "snake_case")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub enum ToneMapping {
+    /// Clamp colors which are too bright.
+    None,
+    /// The Academy Color Encoding System's filmic curve.
+    Aces,
+    /// Reinhard's operator. Softer than ACES, but bright areas can look washed out.
+    Reinhard,
+}
+
+/// How solids are drawn.
+#[derive(
+    Display, FromStr, Copy, Eq, PartialEq, Debug, JsonSchema, Deserialize, Serialize, Sequence, Clone, Ord, PartialOrd,
+)]
+#[serde(rename_all = "snake_case")]
+#[display(style = "snake_case")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export_to = "ModelingCmd.ts"))]
+pub enum RenderMode {
+    /// Shaded faces, without edge lines.
+    Shaded,
+    /// Only edges, so hidden edges show through.
+    Wireframe,
+    /// Only edges, with edges hidden behind faces removed.
+    HiddenLine,
+    /// Shaded faces, with edge lines drawn over them.
+    ShadedWithEdges,
+}
+
 // Enum: Connect Rust Enums to Cpp
 // add our native c++ names for our cxx::ExternType implementation
 #[cfg(feature = "cxx")]
@@ -894,6 +1281,7 @@ impl_extern_type! {
 
     // Graphics engine
     PostEffectType = "Enums::_PostEffectType"
+    RenderMode = "Enums::_RenderMode"
 }
 
 fn bool_true() -> bool {
@@ -963,6 +1351,163 @@ mod tests {
         assert!(c > a);
         assert!(c >= a);
     }
+
+    #[test]
+    fn test_bezier_subdivide_straight() {
+        let p = |x: f64, y: f64| Point3d { x, y, z: 0.0 }.map(LengthUnit);
+        let straight = PathSegment::Bezier {
+            control1: p(1.0, 0.0),
+            control2: p(2.0, 0.0),
+            end: p(3.0, 0.0),
+            relative: false,
+        };
+        let points = straight.subdivide(p(0.0, 0.0), LengthUnit(0.01)).unwrap();
+        assert_eq!(points, vec![p(0.0, 0.0), p(3.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_bezier_subdivide_curved() {
+        let p = |x: f64, y: f64| Point3d { x, y, z: 0.0 }.map(LengthUnit);
+        let start = p(10.0, 10.0);
+        let curved = PathSegment::Bezier {
+            control1: p(0.0, 10.0),
+            control2: p(10.0, 10.0),
+            end: p(10.0, 0.0),
+            relative: true,
+        };
+        let coarse = curved.subdivide(start, LengthUnit(1.0)).unwrap();
+        let fine = curved.subdivide(start, LengthUnit(0.001)).unwrap();
+        assert!(coarse.len() > 2);
+        assert!(fine.len() > coarse.len());
+        for points in [coarse, fine] {
+            assert_eq!(points.first(), Some(&start));
+            // Relative to the start.
+            assert_eq!(points.last(), Some(&p(20.0, 10.0)));
+        }
+        // Other segments can't be subdivided.
+        let line = PathSegment::Line {
+            end: p(1.0, 1.0),
+            relative: false,
+        };
+        assert!(line.subdivide(start, LengthUnit(0.1)).is_none());
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(Padding::new(0.2).map(Padding::get), Some(0.2));
+        assert_eq!(Padding::new(-0.2).map(Padding::get), Some(-0.2));
+        assert_eq!(Padding::new(-1.0), None);
+        assert_eq!(Padding::new(f32::NAN), None);
+        assert_eq!(Padding::default(), Padding::NONE);
+    }
+
+    #[test]
+    fn test_padding_is_checked_when_deserialized() {
+        assert_eq!(serde_json::from_str::<Padding>("0.2").unwrap().get(), 0.2);
+        assert_eq!(serde_json::to_string(&Padding::new(0.5).unwrap()).unwrap(), "0.5");
+        let err = serde_json::from_str::<Padding>("-1.0").unwrap_err();
+        assert!(err.to_string().contains("greater than -1"), "unexpected error {err}");
+    }
+
+    #[test]
+    fn test_window_coord_is_a_plain_point() {
+        let json = serde_json::json!({"type": "mouse_move", "window": {"x": 10.0, "y": 20.0}});
+        let cmd: crate::ModelingCmd = serde_json::from_value(json).unwrap();
+        let crate::ModelingCmd::MouseMove(mouse_move) = &cmd else {
+            panic!("expected MouseMove, got {cmd:?}");
+        };
+        assert_eq!(mouse_move.window, WindowCoord(Point2d { x: 10.0, y: 20.0 }));
+        assert_eq!(
+            serde_json::to_value(mouse_move.window).unwrap(),
+            serde_json::json!({"x": 10.0, "y": 20.0})
+        );
+    }
+
+    #[test]
+    fn test_arc_to_beziers() {
+        let radius = 5.0;
+        let arc = |end_degrees: f64| PathSegment::Arc {
+            center: Point2d {
+                x: LengthUnit(1.0),
+                y: LengthUnit(2.0),
+            },
+            radius: LengthUnit(radius),
+            start: Angle::zero(),
+            end: Angle::from_degrees(end_degrees),
+            relative: false,
+        };
+        for (end_degrees, expected_pieces) in [(90.0, 1), (180.0, 2), (270.0, 3), (100.0, 2), (-90.0, 1)] {
+            let beziers = arc(end_degrees).to_beziers().unwrap();
+            assert_eq!(beziers.len(), expected_pieces, "arc to {end_degrees} degrees");
+            // Sample each bezier, and check it stays close to the circle.
+            let mut pen = Point3d { x: 6.0, y: 2.0, z: 0.0 };
+            for bezier in beziers {
+                let PathSegment::Bezier {
+                    control1,
+                    control2,
+                    end,
+                    relative: false,
+                } = bezier
+                else {
+                    panic!("expected an absolute bezier, got {bezier:?}");
+                };
+                let [p0, p1, p2, p3] = [pen, control1.map(|l| l.0), control2.map(|l| l.0), end.map(|l| l.0)];
+                for i in 0..=10 {
+                    let t = f64::from(i) / 10.0;
+                    let u = 1.0 - t;
+                    let p = p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t);
+                    let dist_from_center = ((p.x - 1.0).powi(2) + (p.y - 2.0).powi(2)).sqrt();
+                    assert!((dist_from_center - radius).abs() <= radius * 3e-4);
+                }
+                pen = p3;
+            }
+            let end = Angle::from_degrees(end_degrees).to_radians();
+            assert!((pen.x - (1.0 + radius * end.cos())).abs() < 1e-9);
+            assert!((pen.y - (2.0 + radius * end.sin())).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_color_from_hex() {
+        let orange = Color::rgba8(255, 136, 0, 255);
+        assert_eq!(Color::from_hex("#f80").unwrap(), orange);
+        assert_eq!(Color::from_hex("ff8800").unwrap(), orange);
+        assert_eq!(Color::from_hex("#FF8800FF").unwrap(), orange);
+        assert_eq!(Color::from_hex("#ff880080").unwrap().a, 128.0 / 255.0);
+        assert_eq!(Color::from_hex("#ff88"), Ok(Color::rgba8(255, 255, 136, 136)));
+        assert_eq!(Color::from_hex("#ff88000"), Err(ColorParseError::InvalidLength(7)));
+        assert_eq!(Color::from_hex("#ff880g"), Err(ColorParseError::InvalidDigit));
+        assert_eq!(Color::from_hex("#ff88é"), Err(ColorParseError::InvalidDigit));
+        assert_eq!(Color::from_hex("#+f+f+f"), Err(ColorParseError::InvalidDigit));
+    }
+
+    #[test]
+    fn test_color_constants() {
+        assert_eq!(Color::WHITE.to_hex(), "#ffffffff");
+        assert_eq!(Color::BLACK.to_hex(), "#000000ff");
+        assert_eq!(Color::RED.to_hex(), "#ff0000ff");
+        assert_eq!(Color::GREEN.to_hex(), "#00ff00ff");
+        assert_eq!(Color::BLUE.to_hex(), "#0000ffff");
+        assert_eq!(Color::TRANSPARENT.to_hex(), "#00000000");
+        assert_eq!(Color::RED.with_alpha(0.5).to_hex(), "#ff000080");
+        // Palettes can be built from 8-bit channels too.
+        const ORANGE: Color = Color::rgba8(255, 136, 0, 255);
+        assert_eq!(ORANGE.to_hex(), "#ff8800ff");
+    }
+
+    #[test]
+    fn test_color_to_hex() {
+        assert_eq!(Color::rgba8(255, 136, 0, 255).to_hex(), "#ff8800ff");
+        assert_eq!(Color::from_hex("#12345678").unwrap().to_hex(), "#12345678");
+        // Out-of-range channels are clamped.
+        let out_of_range = Color {
+            r: 1.5,
+            g: -0.5,
+            b: 0.5,
+            a: 1.0,
+        };
+        assert_eq!(out_of_range.to_hex(), "#ff0080ff");
+    }
 }
 
 /// How a property of an object should be transformed.
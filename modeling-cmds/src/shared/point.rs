@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 mod convert;
+mod distance;
 mod only;
 mod uniform;
 mod zero;
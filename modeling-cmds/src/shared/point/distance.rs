@@ -0,0 +1,63 @@
+use super::{Point2d, Point3d};
+
+impl Point2d<f64> {
+    /// Euclidean distance between this point and `other`.
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        self.distance_squared(other).sqrt()
+    }
+
+    /// Square of the Euclidean distance between this point and `other`.
+    /// Cheaper than [`Point2d::distance_to`], e.g. for comparing distances.
+    pub fn distance_squared(&self, other: &Self) -> f64 {
+        let d = *other - *self;
+        d.x * d.x + d.y * d.y
+    }
+}
+
+impl Point3d<f64> {
+    /// Euclidean distance between this point and `other`.
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        self.distance_squared(other).sqrt()
+    }
+
+    /// Square of the Euclidean distance between this point and `other`.
+    /// Cheaper than [`Point3d::distance_to`], e.g. for comparing distances.
+    pub fn distance_squared(&self, other: &Self) -> f64 {
+        let d = *other - *self;
+        d.x * d.x + d.y * d.y + d.z * d.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_distance() {
+        let p = Point3d {
+            x: 1.0,
+            y: -2.0,
+            z: 3.0,
+        };
+        assert_eq!(p.distance_to(&p), 0.0);
+        assert_eq!(Point2d::zero().distance_squared(&Point2d::zero()), 0.0);
+    }
+
+    #[test]
+    fn axis_aligned() {
+        assert_eq!(Point2d::zero().distance_to(&Point2d::only_y(-4.0)), 4.0);
+        assert_eq!(Point3d::zero().distance_to(&Point3d::only_z(2.5)), 2.5);
+        assert_eq!(Point3d::only_x(1.0).distance_squared(&Point3d::only_x(4.0)), 9.0);
+    }
+
+    #[test]
+    fn diagonal() {
+        let a = Point2d { x: 1.0, y: 1.0 };
+        let b = Point2d { x: 4.0, y: 5.0 };
+        assert_eq!(a.distance_to(&b), 5.0);
+        assert_eq!(b.distance_to(&a), 5.0);
+        assert_eq!(a.distance_squared(&b), 25.0);
+        let c = Point3d { x: 1.0, y: 2.0, z: 2.0 };
+        assert_eq!(Point3d::zero().distance_to(&c), 3.0);
+    }
+}
@@ -0,0 +1,207 @@
+//! Helpers for checking that modeling commands keep the same JSON format across versions of this crate.
+//! [`ModelingCmd::examples`] has an example of every command, e.g. to save as golden files,
+//! and [`assert_cmd_serializes_to`] checks a command against its expected JSON.
+
+use std::{collections::BTreeSet, num::NonZeroU32};
+
+use uuid::Uuid;
+
+use crate::{
+    coord,
+    format::{InputFormat, OutputFormat},
+    id::ModelingCmdId,
+    length_unit::LengthUnit,
+    shared::{
+        Angle, AnnotationOptions, AnnotationTextAlignmentX, AnnotationType, CameraDragInteractionType, CameraMovement,
+        Color, ComponentTransform, CutStrategy, CutType, DirectionalLight, DistanceType, EntityType, ExtrudedFaceInfo,
+        ExtrusionFaceCapType, FileImportFormat, Padding, PathComponentConstraintBound, PathComponentConstraintType,
+        PathSegment, PerspectiveCameraParameters, Point2d, Point3d, PostEffectType, RenderMode, SceneSelectionType,
+        SceneToolType, ToneMapping, Transform, TransformBy, WindowCoord,
+    },
+    units, ImageFormat, ImportFile, ModelingCmd, ModelingCmdEndpoint,
+};
+
+/// Panics unless `cmd` serializes to exactly the `expected` JSON, and that JSON deserializes back to `cmd`.
+#[track_caller]
+pub fn assert_cmd_serializes_to(cmd: &ModelingCmd, expected: serde_json::Value) {
+    let endpoint = ModelingCmdEndpoint::from(cmd);
+    let actual = serde_json::to_value(cmd).unwrap();
+    assert_eq!(actual, expected, "{endpoint} didn't serialize to the expected JSON");
+    let parsed: ModelingCmd = serde_json::from_value(expected)
+        .unwrap_or_else(|e| panic!("expected JSON isn't a valid {endpoint} command: {e}"));
+    assert_eq!(
+        &parsed, cmd,
+        "expected JSON deserialized to a different {endpoint} command"
+    );
+}
+
+/// A type which can make an example of itself, for tests.
+/// Every modeling command implements this, using an example of each of its fields.
+pub trait Example {
+    /// A representative value. Always returns the same value, so it's safe to use in golden files.
+    fn example() -> Self;
+}
+
+const EXAMPLE_ID: Uuid = uuid::uuid!("e1b6a7b4-5c4e-4d6b-9a3f-0c8d2b1f7a91");
+
+macro_rules! impl_example {
+    ($($ty:ty => $example:expr),* $(,)?) => {
+        $(impl Example for $ty {
+            fn example() -> Self {
+                $example
+            }
+        })*
+    };
+}
+
+impl_example! {
+    bool => true,
+    u8 => 1,
+    u32 => 1,
+    f32 => 1.0,
+    f64 => 1.0,
+    NonZeroU32 => NonZeroU32::MIN,
+    String => "example".to_owned(),
+    Uuid => EXAMPLE_ID,
+    ModelingCmdId => ModelingCmdId(EXAMPLE_ID),
+    LengthUnit => LengthUnit(1.0),
+    Angle => Angle::from_degrees(45.0),
+    Color => Color { r: 1.0, g: 0.5, b: 0.0, a: 1.0 },
+    WindowCoord => WindowCoord(Example::example()),
+    Padding => Padding::default(),
+    AnnotationOptions => AnnotationOptions {
+        text: None,
+        line_ends: None,
+        line_width: Some(Example::example()),
+        color: Some(Example::example()),
+        position: Some(Example::example()),
+    },
+    AnnotationTextAlignmentX => AnnotationTextAlignmentX::Center,
+    AnnotationType => AnnotationType::T3D,
+    CameraDragInteractionType => CameraDragInteractionType::Rotate,
+    CameraMovement => CameraMovement::default(),
+    CutStrategy => CutStrategy::default(),
+    CutType => CutType::default(),
+    DistanceType => DistanceType::Euclidean {},
+    EntityType => EntityType::Face,
+    ExtrusionFaceCapType => ExtrusionFaceCapType::Both,
+    ExtrudedFaceInfo => ExtrudedFaceInfo {
+        bottom: Some(Example::example()),
+        top: Example::example(),
+        sides: Vec::new(),
+    },
+    PerspectiveCameraParameters => PerspectiveCameraParameters {
+        fov_y: Some(45.0),
+        z_near: Some(0.1),
+        z_far: Some(1000.0),
+    },
+    PathComponentConstraintBound => PathComponentConstraintBound::default(),
+    PathComponentConstraintType => PathComponentConstraintType::default(),
+    PathSegment => PathSegment::Line {
+        end: Example::example(),
+        relative: false,
+    },
+    PostEffectType => PostEffectType::default(),
+    RenderMode => RenderMode::Shaded,
+    SceneSelectionType => SceneSelectionType::Replace,
+    SceneToolType => SceneToolType::Select,
+    ToneMapping => ToneMapping::Aces,
+    Transform => Transform::default(),
+    ComponentTransform => ComponentTransform {
+        translate: Some(TransformBy {
+            property: Example::example(),
+            set: false,
+            is_local: true,
+        }),
+        rotate_rpy: None,
+        rotate_angle_axis: None,
+        scale: None,
+    },
+    DirectionalLight => DirectionalLight {
+        direction: Example::example(),
+        color: Example::example(),
+        intensity: Example::example(),
+    },
+    ImageFormat => ImageFormat::Png,
+    ImportFile => ImportFile {
+        path: "example.step".to_owned(),
+        data: b"ISO-10303-21;".to_vec(),
+    },
+    InputFormat => InputFormat::from(FileImportFormat::Step),
+    OutputFormat => OutputFormat::step(),
+    coord::System => *coord::KITTYCAD,
+    units::UnitArea => Default::default(),
+    units::UnitDensity => Default::default(),
+    units::UnitLength => Default::default(),
+    units::UnitMass => Default::default(),
+    units::UnitVolume => Default::default(),
+}
+
+impl<T: Example> Example for Option<T> {
+    fn example() -> Self {
+        Some(T::example())
+    }
+}
+
+impl<T: Example> Example for Vec<T> {
+    fn example() -> Self {
+        vec![T::example()]
+    }
+}
+
+impl<T: Example + Ord> Example for BTreeSet<T> {
+    fn example() -> Self {
+        BTreeSet::from([T::example()])
+    }
+}
+
+impl<T: Example, const N: usize> Example for [T; N] {
+    fn example() -> Self {
+        std::array::from_fn(|_| T::example())
+    }
+}
+
+impl<T: Example> Example for Point2d<T> {
+    fn example() -> Self {
+        Point2d {
+            x: T::example(),
+            y: T::example(),
+        }
+    }
+}
+
+impl<T: Example> Example for Point3d<T> {
+    fn example() -> Self {
+        Point3d {
+            x: T::example(),
+            y: T::example(),
+            z: T::example(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_example_round_trips() {
+        for cmd in ModelingCmd::examples() {
+            let json = serde_json::to_value(&cmd).unwrap();
+            assert_cmd_serializes_to(&cmd, json);
+        }
+    }
+
+    #[test]
+    fn serializes_to_expected_json() {
+        let cmd = ModelingCmd::from(crate::SelectGet {});
+        assert_cmd_serializes_to(&cmd, serde_json::json!({"type": "select_get"}));
+    }
+
+    #[test]
+    #[should_panic(expected = "SelectGet didn't serialize to the expected JSON")]
+    fn panics_on_different_json() {
+        let cmd = ModelingCmd::from(crate::SelectGet {});
+        assert_cmd_serializes_to(&cmd, serde_json::json!({"type": "select_clear"}));
+    }
+}
@@ -11,9 +11,10 @@ use uuid::Uuid;
 
 use crate::{
     id::ModelingCmdId,
+    length_unit::LengthUnit,
     ok_response::OkModelingCmdResponse,
-    shared::{EngineErrorCode, ExportFile},
-    ModelingCmd,
+    shared::{EngineErrorCode, ExportFile, PathSegment, Point3d},
+    ClosePath, ExtendPath, Extrude, ModelingCmd, MovePathPen, StartPath,
 };
 
 /// The type of error sent by the KittyCAD API.
@@ -132,6 +133,41 @@ impl std::default::Default for ModelingBatch {
     }
 }
 
+impl Extend<ModelingCmdReq> for ModelingBatch {
+    /// Add the modeling commands to the end of this batch, in order.
+    fn extend<I: IntoIterator<Item = ModelingCmdReq>>(&mut self, iter: I) {
+        self.requests.extend(iter);
+    }
+}
+
+impl FromIterator<ModelingCmdReq> for ModelingBatch {
+    /// Creates a batch of these requests, with a random ID.
+    fn from_iter<I: IntoIterator<Item = ModelingCmdReq>>(iter: I) -> Self {
+        Self {
+            requests: iter.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl IntoIterator for ModelingBatch {
+    type Item = ModelingCmdReq;
+    type IntoIter = std::vec::IntoIter<ModelingCmdReq>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.requests.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ModelingBatch {
+    type Item = &'a ModelingCmdReq;
+    type IntoIter = std::slice::Iter<'a, ModelingCmdReq>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.requests.iter()
+    }
+}
+
 impl ModelingBatch {
     /// Add a new modeling command to the end of this batch.
     pub fn push(&mut self, req: ModelingCmdReq) {
@@ -142,6 +178,177 @@ impl ModelingBatch {
     pub fn is_empty(&self) -> bool {
         self.requests.is_empty()
     }
+
+    /// How many requests are in the batch?
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// How many bytes this batch takes up when serialized to JSON.
+    /// Useful for checking a batch will fit under the WebSocket frame-size limit before sending it.
+    pub fn estimated_serialized_size(&self) -> usize {
+        json_size(self)
+    }
+
+    /// Partition this batch's requests into several batches, each of which serializes to
+    /// at most `max` bytes. Requests keep their order, and each new batch gets a fresh random ID.
+    /// A single request which is bigger than `max` by itself is put into its own batch, which
+    /// will still be over the limit.
+    pub fn split_at_size(self, max: usize) -> Vec<ModelingBatch> {
+        let responses = self.responses;
+        let new_batch = || ModelingBatch {
+            responses,
+            ..Default::default()
+        };
+        // Size of the batch with no requests, i.e. everything except the requests themselves.
+        let overhead = new_batch().estimated_serialized_size();
+
+        let mut batches = Vec::new();
+        let mut current = new_batch();
+        let mut current_size = overhead;
+        for req in self.requests {
+            let req_size = json_size(&req);
+            // Every request after the first is preceded by a comma.
+            let added = if current.is_empty() { req_size } else { req_size + 1 };
+            if !current.is_empty() && current_size + added > max {
+                batches.push(std::mem::replace(&mut current, new_batch()));
+                current_size = overhead + req_size;
+            } else {
+                current_size += added;
+            }
+            current.push(req);
+        }
+        if !current.is_empty() {
+            batches.push(current);
+        }
+        batches
+    }
+}
+
+/// Build a batch which appends each segment to the given path, in order, one `ExtendPath` per segment.
+/// `id_gen` gives each command its ID, and is called once more for the batch's ID.
+/// Use e.g. `|| Uuid::new_v4().into()` for random IDs.
+pub fn batch_path_segments(
+    path_id: ModelingCmdId,
+    segments: impl IntoIterator<Item = PathSegment>,
+    mut id_gen: impl FnMut() -> ModelingCmdId,
+) -> ModelingBatch {
+    let requests = segments
+        .into_iter()
+        .map(|segment| {
+            let cmd = ModelingCmd::ExtendPath(ExtendPath { path: path_id, segment });
+            debug_assert!(cmd.is_safe_to_batch(), "ExtendPath should always be safe to batch");
+            ModelingCmdReq { cmd, cmd_id: id_gen() }
+        })
+        .collect();
+    ModelingBatch {
+        requests,
+        batch_id: id_gen(),
+        responses: false,
+    }
+}
+
+/// Builds the commands to draw a closed profile and extrude it into a solid:
+/// `StartPath`, `MovePathPen`, one `ExtendPath` per segment, `ClosePath`, then `Extrude`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchBuilder {
+    start: Point3d<LengthUnit>,
+    segments: Vec<PathSegment>,
+}
+
+/// Commands to create and extrude a profile, from [`SketchBuilder::build_extrude`].
+#[derive(Debug, Clone)]
+pub struct SketchExtrusion {
+    /// The commands to send, in order.
+    pub requests: Vec<ModelingCmdReq>,
+    /// ID of the path (the `StartPath` command).
+    pub path_id: ModelingCmdId,
+    /// ID of the extruded solid.
+    /// The engine turns the closed path into the solid, so this is the same as `path_id`.
+    pub solid_id: ModelingCmdId,
+}
+
+impl SketchBuilder {
+    /// Start a profile with the pen at `start`.
+    pub fn new(start: Point3d<LengthUnit>) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add a segment, starting where the previous one ended.
+    pub fn segment(mut self, segment: PathSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Close the profile and extrude it by `distance`, giving each command a random ID.
+    pub fn build_extrude(self, distance: LengthUnit) -> SketchExtrusion {
+        self.build_extrude_with_ids(distance, || Uuid::new_v4().into())
+    }
+
+    /// Like [`SketchBuilder::build_extrude`], but `id_gen` gives each command its ID, in order.
+    pub fn build_extrude_with_ids(
+        self,
+        distance: LengthUnit,
+        mut id_gen: impl FnMut() -> ModelingCmdId,
+    ) -> SketchExtrusion {
+        let path_id = id_gen();
+        let mut requests = Vec::with_capacity(self.segments.len() + 4);
+        requests.push(ModelingCmdReq {
+            cmd: ModelingCmd::StartPath(StartPath {}),
+            cmd_id: path_id,
+        });
+        requests.push(ModelingCmdReq {
+            cmd: ModelingCmd::MovePathPen(MovePathPen {
+                path: path_id,
+                to: self.start,
+            }),
+            cmd_id: id_gen(),
+        });
+        requests.extend(self.segments.into_iter().map(|segment| ModelingCmdReq {
+            cmd: ModelingCmd::ExtendPath(ExtendPath { path: path_id, segment }),
+            cmd_id: id_gen(),
+        }));
+        requests.push(ModelingCmdReq {
+            cmd: ModelingCmd::ClosePath(ClosePath {
+                path_id: path_id.into(),
+            }),
+            cmd_id: id_gen(),
+        });
+        requests.push(ModelingCmdReq {
+            cmd: ModelingCmd::Extrude(Extrude {
+                target: path_id,
+                distance,
+                faces: None,
+            }),
+            cmd_id: id_gen(),
+        });
+        SketchExtrusion {
+            requests,
+            path_id,
+            solid_id: path_id,
+        }
+    }
+}
+
+/// Number of bytes `value` takes up when serialized to JSON, without allocating a buffer for it.
+fn json_size<T: Serialize>(value: &T) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    // Our types always serialize successfully, and writing to the counter never fails.
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
 }
 
 /// Representation of an ICE server used for STUN/TURN
@@ -185,6 +392,13 @@ pub enum OkWebSocketResponseData {
         /// The result of the command.
         modeling_response: OkModelingCmdResponse,
     },
+    /// Progress of a long-running modeling command, e.g. a large import or loft.
+    /// The engine may send any number of these before the command's response,
+    /// with the command's ID as their request ID.
+    ModelingProgress {
+        /// How much of the command is done, from 0 (just started) to 1 (finished).
+        progress: f32,
+    },
     /// Response to a ModelingBatch.
     ModelingBatch {
         /// For each request in the batch,
@@ -224,6 +438,33 @@ pub struct SuccessWebSocketResponse {
     /// The data sent with a successful response.
     /// This will be flattened into a 'type' and 'data' field.
     pub resp: OkWebSocketResponseData,
+    /// Caveats about how the request succeeded, e.g. a fillet whose radius had to be reduced.
+    /// Usually empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<EngineWarning>,
+}
+
+/// A caveat about a request which succeeded, but not exactly as requested.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone)]
+pub struct EngineWarning {
+    /// What kind of warning this is.
+    pub code: WarningCode,
+    /// Details for the user.
+    pub message: String,
+}
+
+/// The type of warning sent by the KittyCAD engine.
+#[derive(Display, FromStr, Copy, Eq, PartialEq, Debug, JsonSchema, Deserialize, Serialize, Clone, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCode {
+    /// The engine had to change a parameter to complete the request,
+    /// e.g. reducing a fillet's radius so that it fits.
+    ParameterAdjusted,
+    /// The result is an approximation of what was requested.
+    Approximated,
+    /// A warning this version of the crate doesn't know about.
+    #[serde(other)]
+    Other,
 }
 
 /// Unsuccessful Websocket response.
@@ -277,6 +518,7 @@ impl WebSocketResponse {
             success: true,
             request_id,
             resp,
+            warnings: Vec::new(),
         })
     }
 
@@ -331,6 +573,15 @@ impl From<ExportFile> for RawFile {
     }
 }
 
+impl From<RawFile> for ExportFile {
+    fn from(f: RawFile) -> Self {
+        Self {
+            name: f.name,
+            contents: f.contents.into(),
+        }
+    }
+}
+
 /// An error with an internal message for logging.
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LoggableApiError {
@@ -809,6 +1060,7 @@ mod tests {
                     control_points: vec![],
                 }),
             },
+            warnings: vec![],
         });
         let expected = serde_json::json!({
             "success": true,
@@ -832,6 +1084,7 @@ mod tests {
             success: true,
             request_id: Some(REQ_ID),
             resp: OkWebSocketResponseData::IceServerInfo { ice_servers: vec![] },
+            warnings: vec![],
         });
         let expected = serde_json::json!({
             "success": true,
@@ -852,6 +1105,7 @@ mod tests {
             success: true,
             request_id: Some(REQ_ID),
             resp: OkWebSocketResponseData::Export { files: vec![] },
+            warnings: vec![],
         });
         let expected = serde_json::json!({
             "success": true,
@@ -864,6 +1118,24 @@ mod tests {
         assert_json_eq(actual, expected);
     }
 
+    #[test]
+    fn deserialize_websocket_ok_with_warnings() {
+        let json = serde_json::json!({
+            "success": true,
+            "request_id": "cc30d5e2-482b-4498-b5d2-6131c30a50a4",
+            "resp": { "type": "pong", "data": {} },
+            "warnings": [
+                { "code": "parameter_adjusted", "message": "fillet radius reduced to 1mm" },
+                { "code": "some_future_warning", "message": "?" }
+            ]
+        });
+        let WebSocketResponse::Success(resp) = serde_json::from_value(json).unwrap() else {
+            panic!("should be a success");
+        };
+        let codes: Vec<_> = resp.warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, vec![WarningCode::ParameterAdjusted, WarningCode::Other]);
+    }
+
     #[test]
     fn serialize_websocket_err() {
         let actual = WebSocketResponse::Failure(FailureWebSocketResponse {
@@ -917,6 +1189,174 @@ mod tests {
         assert_json_eq(actual, expected);
     }
 
+    #[test]
+    fn batch_split_at_size() {
+        let mut batch = ModelingBatch {
+            responses: true,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            batch.push(ModelingCmdReq {
+                cmd: ModelingCmd::SelectClear(crate::SelectClear {}),
+                cmd_id: Uuid::new_v4().into(),
+            });
+        }
+        let total = batch.estimated_serialized_size();
+        assert_eq!(total, serde_json::to_vec(&batch).unwrap().len());
+        let original_ids: Vec<_> = batch.requests.iter().map(|req| req.cmd_id).collect();
+        let original_batch_id = batch.batch_id;
+
+        let max = total / 3;
+        let split = batch.split_at_size(max);
+        assert!(split.len() > 1);
+        for b in &split {
+            assert!(!b.is_empty());
+            assert!(b.responses);
+            assert_ne!(b.batch_id, original_batch_id);
+            assert!(b.estimated_serialized_size() <= max);
+        }
+        let split_ids: Vec<_> = split
+            .iter()
+            .flat_map(|b| b.requests.iter().map(|req| req.cmd_id))
+            .collect();
+        assert_eq!(split_ids, original_ids);
+    }
+
+    #[test]
+    fn batch_path_segments_in_order() {
+        use crate::{
+            length_unit::LengthUnit,
+            shared::{Angle, Point2d, Point3d},
+        };
+
+        let point = |x: f64, y: f64| Point3d {
+            x: LengthUnit(x),
+            y: LengthUnit(y),
+            z: LengthUnit(0.0),
+        };
+        let segments = vec![
+            PathSegment::Line {
+                end: point(1.0, 0.0),
+                relative: false,
+            },
+            PathSegment::Arc {
+                center: Point2d {
+                    x: LengthUnit(1.0),
+                    y: LengthUnit(1.0),
+                },
+                radius: LengthUnit(1.0),
+                start: Angle::from_degrees(-90.0),
+                end: Angle::zero(),
+                relative: false,
+            },
+            PathSegment::Bezier {
+                control1: point(0.0, 1.0),
+                control2: point(-1.0, 1.0),
+                end: point(-1.0, 0.0),
+                relative: true,
+            },
+        ];
+        let path_id = ModelingCmdId(REQ_ID);
+        let mut next_id = 0;
+        let batch = batch_path_segments(path_id, segments.clone(), || {
+            next_id += 1;
+            Uuid::from_u128(next_id).into()
+        });
+
+        assert_eq!(batch.requests.len(), segments.len());
+        for (i, (req, segment)) in batch.requests.iter().zip(segments).enumerate() {
+            assert_eq!(req.cmd_id, ModelingCmdId(Uuid::from_u128(i as u128 + 1)));
+            assert_eq!(req.cmd, ModelingCmd::ExtendPath(ExtendPath { path: path_id, segment }));
+        }
+        // The batch ID is generated after every command's ID.
+        assert_eq!(batch.batch_id, ModelingCmdId(Uuid::from_u128(4)));
+        assert!(!batch.responses);
+    }
+
+    #[test]
+    fn sketch_builder_emits_commands_in_order() {
+        let point = |x: f64, y: f64| Point3d {
+            x: LengthUnit(x),
+            y: LengthUnit(y),
+            z: LengthUnit(0.0),
+        };
+        let line = |x, y| PathSegment::Line {
+            end: point(x, y),
+            relative: false,
+        };
+        let mut next_id = 0;
+        let sketch = SketchBuilder::new(point(0.0, 0.0))
+            .segment(line(1.0, 0.0))
+            .segment(line(1.0, 1.0))
+            .segment(line(0.0, 1.0))
+            .build_extrude_with_ids(LengthUnit(2.0), || {
+                next_id += 1;
+                ModelingCmdId(Uuid::from_u128(next_id))
+            });
+
+        let path_id = ModelingCmdId(Uuid::from_u128(1));
+        assert_eq!(sketch.path_id, path_id);
+        assert_eq!(sketch.solid_id, path_id);
+        let expected = [
+            ModelingCmd::StartPath(StartPath {}),
+            ModelingCmd::MovePathPen(MovePathPen {
+                path: path_id,
+                to: point(0.0, 0.0),
+            }),
+            ModelingCmd::ExtendPath(ExtendPath {
+                path: path_id,
+                segment: line(1.0, 0.0),
+            }),
+            ModelingCmd::ExtendPath(ExtendPath {
+                path: path_id,
+                segment: line(1.0, 1.0),
+            }),
+            ModelingCmd::ExtendPath(ExtendPath {
+                path: path_id,
+                segment: line(0.0, 1.0),
+            }),
+            ModelingCmd::ClosePath(ClosePath {
+                path_id: path_id.into(),
+            }),
+            ModelingCmd::Extrude(Extrude {
+                target: path_id,
+                distance: LengthUnit(2.0),
+                faces: None,
+            }),
+        ];
+        assert_eq!(sketch.requests.len(), expected.len());
+        for (i, (req, cmd)) in sketch.requests.iter().zip(expected).enumerate() {
+            assert_eq!(req.cmd_id, ModelingCmdId(Uuid::from_u128(i as u128 + 1)));
+            assert_eq!(req.cmd, cmd);
+        }
+    }
+
+    #[test]
+    fn batch_from_iter() {
+        let reqs: Vec<_> = (1..=3)
+            .map(|i| ModelingCmdReq {
+                cmd: ModelingCmd::SelectClear(crate::SelectClear {}),
+                cmd_id: ModelingCmdId(Uuid::from_u128(i)),
+            })
+            .collect();
+
+        let mut batch: ModelingBatch = std::iter::empty().collect();
+        assert!(batch.is_empty());
+        assert_eq!(batch.len(), 0);
+        assert!(!batch.responses);
+
+        batch.extend(reqs.iter().cloned());
+        assert!(!batch.is_empty());
+        assert_eq!(batch.len(), reqs.len());
+
+        let batch: ModelingBatch = reqs.iter().cloned().collect();
+        assert_eq!(batch.len(), reqs.len());
+        let ids: Vec<_> = (&batch).into_iter().map(|req| req.cmd_id).collect();
+        assert_eq!(ids, reqs.iter().map(|req| req.cmd_id).collect::<Vec<_>>());
+        let owned: Vec<_> = batch.into_iter().map(|req| req.cmd_id).collect();
+        assert_eq!(owned, ids);
+    }
+
     fn assert_json_eq<T: Serialize>(actual: T, expected: serde_json::Value) {
         let json_str = serde_json::to_string(&actual).unwrap();
         let actual: serde_json::Value = serde_json::from_str(&json_str).unwrap();
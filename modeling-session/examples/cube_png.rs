@@ -35,6 +35,8 @@ async fn main() -> Result<()> {
         buffer_reqs: None,
         await_response_timeout: None,
         show_grid: None,
+        max_commands_per_second: None,
+        retry_policy: None,
     };
     let mut session = Session::start(session_builder)
         .await
@@ -1,118 +1,143 @@
-use std::{collections::HashMap, time::Duration};
-
-use futures::{
-    stream::{SplitSink, SplitStream},
-    SinkExt, StreamExt,
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
 };
+
+use futures::{future::Either, Sink, SinkExt, Stream, StreamExt};
 use kittycad_modeling_cmds::{
     id::ModelingCmdId,
     ok_response::OkModelingCmdResponse,
-    websocket::{ModelingBatch, ModelingCmdReq, OkWebSocketResponseData, WebSocketRequest, WebSocketResponse},
+    shared::ExportFile,
+    websocket::{
+        BatchResponse, EngineWarning, ModelingBatch, ModelingCmdReq, OkWebSocketResponseData, SuccessWebSocketResponse,
+        WebSocketRequest, WebSocketResponse,
+    },
+    ModelingCmd,
 };
-use reqwest::Upgraded;
 use tokio::{
     sync::{mpsc, oneshot},
     time::Instant,
 };
-use tokio_tungstenite::{tungstenite::Message as WsMsg, WebSocketStream};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMsg, Result as WsResult};
 
-use crate::RunCommandError;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::{rate_limit::RateLimiter, RunCommandError};
 
 type Result<T> = std::result::Result<T, RunCommandError>;
 
 pub enum Request {
     SendModelingCmd(ModelingCmdReq, oneshot::Sender<Result<()>>),
-    GetResponse(ModelingCmdId, oneshot::Sender<Result<OkModelingCmdResponse>>),
+    GetResponse(
+        ModelingCmdId,
+        oneshot::Sender<Result<(OkModelingCmdResponse, Vec<EngineWarning>)>>,
+    ),
+    GetExportFiles(ModelingCmdId, oneshot::Sender<Result<Vec<ExportFile>>>),
     SendModelingBatch(ModelingBatch, oneshot::Sender<Result<()>>),
+    /// Stop waiting for this command's response, and drop the response if it arrives later.
+    Cancel(ModelingCmdId),
+    /// Send the progress of this command, as the engine reports it, until its response arrives.
+    WatchProgress(ModelingCmdId, mpsc::UnboundedSender<f32>),
+    /// Get the responses to each command in a batch (sent with `responses: true`),
+    /// in the same order as the given command IDs.
+    GetBatchResponses {
+        batch_id: ModelingCmdId,
+        cmd_ids: Vec<ModelingCmdId>,
+        responder: oneshot::Sender<Result<Vec<Result<OkModelingCmdResponse>>>>,
+    },
 }
 
 pub async fn start(
     mut incoming: mpsc::Receiver<Request>,
-    mut write_to_ws: SplitSink<WebSocketStream<Upgraded>, WsMsg>,
-    mut read_from_ws: SplitStream<WebSocketStream<Upgraded>>,
+    mut write_to_ws: impl Sink<WsMsg, Error = WsError> + Unpin,
+    mut read_from_ws: impl Stream<Item = WsResult<WsMsg>> + Unpin,
     timeout: Duration,
+    mut rate_limiter: Option<RateLimiter>,
+    #[cfg(feature = "metrics")] metrics: Metrics,
 ) {
-    let mut responses: HashMap<ModelingCmdId, WebSocketResponse> = HashMap::new();
-    'next_request: while let Some(req) = incoming.recv().await {
+    let mut responses = Responses {
+        #[cfg(feature = "metrics")]
+        metrics,
+        ..Default::default()
+    };
+    while let Some(req) = incoming.recv().await {
         match req {
             Request::SendModelingCmd(cmd, responder) => {
-                let ws_msg = WsMsg::Text(
-                    serde_json::to_string(&WebSocketRequest::ModelingCmdReq(cmd))
-                        .expect("ModelingCmdReq can always be serialized"),
-                );
-                let resp = write_to_ws.send(ws_msg).await.map_err(RunCommandError::WebSocketSend);
+                if let Err(e) = wait_for_rate_limit(rate_limiter.as_mut(), timeout).await {
+                    let _ = responder.send(Err(e));
+                    continue;
+                }
+                #[cfg(feature = "metrics")]
+                responses.metrics.sent(cmd.cmd_id, [&cmd.cmd]);
+                let resp = send_request(&mut write_to_ws, &WebSocketRequest::ModelingCmdReq(cmd)).await;
                 // If the send fails, it's because the caller dropped its end, so ignore the
                 // error because we're done with this request anyway.
                 let _ = responder.send(resp);
             }
-            Request::GetResponse(cmd_id, responder) => {
-                let start = Instant::now();
-                while start.elapsed() < timeout {
-                    // Check the response map.
-                    // If we've already got the response for this ID, then send it back to the user!
-                    if let Some(resp) = responses.remove(&cmd_id) {
-                        let send_this_to_user = match resp {
-                            WebSocketResponse::Success(s) => {
-                                let resp = s.resp;
-                                match resp {
-                                    OkWebSocketResponseData::Modeling { modeling_response } => Ok(modeling_response),
-                                    _ => {
-                                        // This request ID should be for a modeling request. Something's gone very wrong.
-                                        Err(RunCommandError::ServerSentWrongType)
-                                    }
-                                }
-                            }
-                            WebSocketResponse::Failure(e) => Err(RunCommandError::ModelingApiFailure {
-                                request_id: Some(cmd_id.into()),
-                                errors: e.errors,
-                            }),
-                        };
-                        // If the send fails, it's because the caller dropped its end, so ignore the
-                        // error because we're done with this request anyway.
-                        let _ = responder.send(send_this_to_user);
-                        // Finished this request! Actor is ready for the next request.
-                        continue 'next_request;
-                    }
-                    // If not, get a response from the WebSocket.
-                    // If we can't get any response, the WebSocket must have been closed.
-                    let Some(msg) = read_from_ws.next().await else {
-                        // If the send fails, it's because the caller dropped its end, so ignore
-                        // the error because we're done with this request anyway.
-                        let _ = responder.send(Err(RunCommandError::WebSocketClosed));
-                        // Probably no point getting another request, but the user may try to,
-                        // so we should respect them.
-                        continue 'next_request;
-                    };
-                    // Couldn't read from WebSocket? Try again.
-                    let Ok(msg) = msg else {
-                        continue;
-                    };
-                    // WebSocket response wasn't text? Try again.
-                    let Some(resp_text) = text_from_ws(msg) else {
-                        continue;
-                    };
-                    // Couldn't decode the response? Try again.
-                    let Ok(resp) = decode_websocket_text(&resp_text) else {
-                        continue;
-                    };
-                    if let Some(id) = resp.request_id() {
-                        responses.insert(id.into(), resp);
-                    } else {
-                        continue;
+            Request::Cancel(cmd_id) => responses.cancel(cmd_id),
+            Request::WatchProgress(cmd_id, progress) => {
+                responses.progress.insert(cmd_id, progress);
+            }
+            Request::GetResponse(cmd_id, mut responder) => {
+                let resp = wait_for_response(cmd_id, &mut responses, &mut read_from_ws, timeout, &mut responder)
+                    .await
+                    .and_then(|resp| match resp.resp {
+                        OkWebSocketResponseData::Modeling { modeling_response } => {
+                            Ok((modeling_response, resp.warnings))
+                        }
+                        // This request ID should be for a modeling request. Something's gone very wrong.
+                        _ => Err(RunCommandError::ServerSentWrongType),
+                    });
+                // If the send fails, it's because the caller dropped its end, so ignore the
+                // error because we're done with this request anyway.
+                let _ = responder.send(resp);
+            }
+            Request::GetExportFiles(cmd_id, mut responder) => {
+                // The engine acknowledges the `Export` command with a modeling response,
+                // then sends the exported files in a separate message with the same request ID.
+                let resp = loop {
+                    let resp =
+                        wait_for_response(cmd_id, &mut responses, &mut read_from_ws, timeout, &mut responder).await;
+                    match resp.map(|resp| resp.resp) {
+                        Ok(OkWebSocketResponseData::Export { files }) => {
+                            break Ok(files.into_iter().map(ExportFile::from).collect());
+                        }
+                        Ok(OkWebSocketResponseData::Modeling { .. }) => continue,
+                        Ok(_) => break Err(RunCommandError::ServerSentWrongType),
+                        Err(e) => break Err(e),
                     }
-                }
-                // If the send fails, it's because the caller dropped its end, so cancel this request
-                // and wait for the next request.
-                if responder.send(Err(RunCommandError::TimeOutWaitingForResponse)).is_err() {
-                    continue 'next_request;
-                }
+                };
+                // If the send fails, it's because the caller dropped its end, so ignore the
+                // error because we're done with this request anyway.
+                let _ = responder.send(resp);
+            }
+            Request::GetBatchResponses {
+                batch_id,
+                cmd_ids,
+                mut responder,
+            } => {
+                // The engine replies to the whole batch with a single message, whose request ID is the batch ID.
+                let resp = wait_for_response(batch_id, &mut responses, &mut read_from_ws, timeout, &mut responder)
+                    .await
+                    .and_then(|resp| match resp.resp {
+                        OkWebSocketResponseData::ModelingBatch { responses } => Ok(demux_batch(&cmd_ids, responses)),
+                        // This request ID should be for a batch request. Something's gone very wrong.
+                        _ => Err(RunCommandError::ServerSentWrongType),
+                    });
+                // If the send fails, it's because the caller dropped its end, so ignore the
+                // error because we're done with this request anyway.
+                let _ = responder.send(resp);
             }
             Request::SendModelingBatch(batch, responder) => {
-                let ws_msg = WsMsg::Text(
-                    serde_json::to_string(&WebSocketRequest::ModelingCmdBatchReq(batch))
-                        .expect("ModelingCmdReq can always be serialized"),
-                );
-                let resp = write_to_ws.send(ws_msg).await.map_err(RunCommandError::WebSocketSend);
+                if let Err(e) = wait_for_rate_limit(rate_limiter.as_mut(), timeout).await {
+                    let _ = responder.send(Err(e));
+                    continue;
+                }
+                #[cfg(feature = "metrics")]
+                responses
+                    .metrics
+                    .sent(batch.batch_id, batch.requests.iter().map(|req| &req.cmd));
+                let resp = send_request(&mut write_to_ws, &WebSocketRequest::ModelingCmdBatchReq(batch)).await;
                 // If the send fails, it's because the caller dropped its end, so ignore the
                 // error because we're done with this request anyway.
                 let _ = responder.send(resp);
@@ -121,6 +146,185 @@ pub async fn start(
     }
 }
 
+/// Responses which have been read from the WebSocket, but not yet sent to the user.
+#[derive(Default)]
+struct Responses {
+    received: HashMap<ModelingCmdId, WebSocketResponse>,
+    /// Commands the user has cancelled, so their responses should be dropped.
+    cancelled: HashSet<ModelingCmdId>,
+    /// Where to send the progress of commands whose progress the user is watching.
+    progress: HashMap<ModelingCmdId, mpsc::UnboundedSender<f32>>,
+    /// Statistics about the requests and their responses.
+    #[cfg(feature = "metrics")]
+    metrics: Metrics,
+}
+
+impl Responses {
+    fn insert(&mut self, cmd_id: ModelingCmdId, resp: WebSocketResponse) {
+        // Progress isn't the command's response, so pass it on (if anyone's watching) and keep waiting.
+        if let WebSocketResponse::Success(SuccessWebSocketResponse {
+            resp: OkWebSocketResponseData::ModelingProgress { progress },
+            ..
+        }) = &resp
+        {
+            if let Some(watcher) = self.progress.get(&cmd_id) {
+                let _ = watcher.send(*progress);
+            }
+            return;
+        }
+        #[cfg(feature = "metrics")]
+        self.metrics.received(cmd_id, &resp);
+        // Nobody's waiting for a cancelled command's response, so don't keep it.
+        if !self.cancelled.remove(&cmd_id) {
+            self.received.insert(cmd_id, resp);
+        }
+    }
+
+    /// Take the response to the given request, if it's arrived.
+    fn take(&mut self, cmd_id: ModelingCmdId) -> Option<Result<SuccessWebSocketResponse>> {
+        let resp = self.received.remove(&cmd_id)?;
+        // The command is done, so stop sending its progress.
+        self.progress.remove(&cmd_id);
+        Some(match resp {
+            WebSocketResponse::Success(s) => Ok(s),
+            WebSocketResponse::Failure(e) => Err(RunCommandError::ModelingApiFailure {
+                request_id: Some(cmd_id.into()),
+                errors: e.errors,
+            }),
+        })
+    }
+
+    fn cancel(&mut self, cmd_id: ModelingCmdId) {
+        #[cfg(feature = "metrics")]
+        self.metrics.forget(cmd_id);
+        self.progress.remove(&cmd_id);
+        // If the response has already arrived, dropping it is enough. Otherwise, drop it when it arrives.
+        if self.received.remove(&cmd_id).is_none() {
+            self.cancelled.insert(cmd_id);
+        }
+    }
+}
+
+/// Wait for the response to the given request, reading from the WebSocket until it arrives.
+/// Responses to other requests are stored in `responses`, so they can be returned later.
+/// Stops waiting if the request is cancelled, or if the user stops waiting (i.e. drops their end of `responder`),
+/// in which case the response is dropped when it arrives.
+async fn wait_for_response<T>(
+    cmd_id: ModelingCmdId,
+    responses: &mut Responses,
+    read_from_ws: &mut (impl Stream<Item = WsResult<WsMsg>> + Unpin),
+    timeout: Duration,
+    responder: &mut oneshot::Sender<T>,
+) -> Result<SuccessWebSocketResponse> {
+    // The cancellation stays until the response arrives, so the response is still dropped.
+    if responses.cancelled.contains(&cmd_id) {
+        return Err(RunCommandError::Cancelled);
+    }
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        // Check the response map.
+        // If we've already got the response for this ID, then send it back to the user!
+        if let Some(resp) = responses.take(cmd_id) {
+            return resp;
+        }
+        // If not, get a response from the WebSocket.
+        // If the user stops waiting in the meantime, so should we.
+        let msg = match futures::future::select(read_from_ws.next(), std::pin::pin!(responder.closed())).await {
+            Either::Left((msg, _)) => msg,
+            Either::Right(_) => {
+                responses.cancel(cmd_id);
+                return Err(RunCommandError::Cancelled);
+            }
+        };
+        // If we can't get any response, the WebSocket must have been closed.
+        let Some(msg) = msg else {
+            return Err(RunCommandError::WebSocketClosed);
+        };
+        // Couldn't read from WebSocket? Try again.
+        let Ok(msg) = msg else {
+            continue;
+        };
+        // Couldn't decode the response, or it wasn't a response at all? Try again.
+        let Some(resp) = decode_websocket_msg(msg) else {
+            continue;
+        };
+        if let Some(id) = resp.request_id() {
+            responses.insert(id.into(), resp);
+        }
+    }
+    #[cfg(feature = "metrics")]
+    responses.metrics.timed_out(cmd_id);
+    responses.progress.remove(&cmd_id);
+    Err(RunCommandError::TimeOutWaitingForResponse)
+}
+
+/// Split the engine's response to a whole batch into the response for each command in it.
+/// If a command failed, the engine doesn't run the commands after it, so they have no response.
+fn demux_batch(
+    cmd_ids: &[ModelingCmdId],
+    mut responses: HashMap<ModelingCmdId, BatchResponse>,
+) -> Vec<Result<OkModelingCmdResponse>> {
+    cmd_ids
+        .iter()
+        .map(|cmd_id| match responses.remove(cmd_id) {
+            Some(BatchResponse::Success { response }) => Ok(response),
+            Some(BatchResponse::Failure { errors }) => Err(RunCommandError::ModelingApiFailure {
+                request_id: Some((*cmd_id).into()),
+                errors,
+            }),
+            None => Err(RunCommandError::NotRunInBatch),
+        })
+        .collect()
+}
+
+/// If there's a rate limit, wait until it allows another send.
+/// Errors if that would take longer than the timeout.
+async fn wait_for_rate_limit(rate_limiter: Option<&mut RateLimiter>, timeout: Duration) -> Result<()> {
+    let Some(rate_limiter) = rate_limiter else {
+        return Ok(());
+    };
+    if rate_limiter.time_until_available() > timeout {
+        return Err(RunCommandError::TimeOutWaitingForResponse);
+    }
+    rate_limiter.acquire().await;
+    Ok(())
+}
+
+/// Send a request to the engine over the WebSocket.
+async fn send_request(
+    write_to_ws: &mut (impl Sink<WsMsg, Error = WsError> + Unpin),
+    req: &WebSocketRequest,
+) -> Result<()> {
+    let ws_msg = encode_request(req)?;
+    write_to_ws
+        .send(ws_msg)
+        .await
+        .map_err(|e| RunCommandError::WebSocketSend(Box::new(e)))
+}
+
+/// Encode a request as a WebSocket message.
+/// Requests carrying raw file bytes are sent as binary BSON, as the engine expects, because JSON would
+/// spell out every byte as a number. Everything else is sent as JSON text.
+fn encode_request(req: &WebSocketRequest) -> Result<WsMsg> {
+    if has_raw_files(req) {
+        let bytes = bson::to_vec(req).map_err(RunCommandError::BsonEncode)?;
+        Ok(WsMsg::Binary(bytes))
+    } else {
+        let text = serde_json::to_string(req).expect("WebSocketRequest can always be serialized");
+        Ok(WsMsg::Text(text))
+    }
+}
+
+/// Does this request contain raw file bytes, i.e. files to import?
+fn has_raw_files(req: &WebSocketRequest) -> bool {
+    let has_files = |req: &ModelingCmdReq| matches!(req.cmd, ModelingCmd::ImportFiles(_));
+    match req {
+        WebSocketRequest::ModelingCmdReq(req) => has_files(req),
+        WebSocketRequest::ModelingCmdBatchReq(batch) => batch.requests.iter().any(has_files),
+        _ => false,
+    }
+}
+
 /// Given the text from a WebSocket, deserialize its JSON.
 /// Returns OK if the WebSocket's JSON represents a successful response.
 /// Returns an error if the WebSocket's JSON represented a failure response.
@@ -129,10 +333,220 @@ fn decode_websocket_text(text: &str) -> Result<WebSocketResponse> {
     Ok(resp)
 }
 
-/// Find the text in a WebSocket message, if there's any.
-fn text_from_ws(msg: WsMsg) -> Option<String> {
+/// Decode a response from a WebSocket message.
+/// Most responses are JSON text, but exported files are sent as binary BSON, because JSON would spell out every
+/// byte as a number. Returns `None` if the message isn't a response (e.g. it's a ping), or couldn't be decoded.
+fn decode_websocket_msg(msg: WsMsg) -> Option<WebSocketResponse> {
     match msg {
-        WsMsg::Text(text) => Some(text),
+        WsMsg::Text(text) => decode_websocket_text(&text).ok(),
+        WsMsg::Binary(bytes) => bson::from_slice(&bytes).ok(),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demux_recorded_batch_response() {
+        let ok_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let failed_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b02".parse().unwrap();
+        let not_run_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b03".parse().unwrap();
+        let frame = r#"{
+            "success": true,
+            "request_id": "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b00",
+            "resp": {
+                "type": "modeling_batch",
+                "data": {
+                    "responses": {
+                        "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01": {
+                            "response": { "type": "empty" }
+                        },
+                        "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b02": {
+                            "errors": [{ "error_code": "bad_request", "message": "nothing to extrude" }]
+                        }
+                    }
+                }
+            }
+        }"#;
+        let WebSocketResponse::Success(resp) = decode_websocket_text(frame).unwrap() else {
+            panic!("frame should be a success");
+        };
+        let OkWebSocketResponseData::ModelingBatch { responses } = resp.resp else {
+            panic!("frame should be a batch response");
+        };
+
+        let results = demux_batch(&[ok_id, failed_id, not_run_id], responses);
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Ok(OkModelingCmdResponse::Empty)));
+        assert!(matches!(
+            &results[1],
+            Err(RunCommandError::ModelingApiFailure { request_id: Some(id), errors }) if *id == failed_id.0 && errors.len() == 1
+        ));
+        assert!(matches!(results[2], Err(RunCommandError::NotRunInBatch)));
+    }
+
+    #[test]
+    fn pipelined_responses_match_their_commands() {
+        let ids: Vec<ModelingCmdId> = (1..=3).map(|i| ModelingCmdId(uuid::Uuid::from_u128(i))).collect();
+        let mut responses = Responses::default();
+        // Pipelined commands' responses can arrive in any order.
+        for &id in ids.iter().rev() {
+            let resp = if id == ids[1] {
+                WebSocketResponse::failure(Some(id.into()), Vec::new())
+            } else {
+                WebSocketResponse::success(Some(id.into()), OkWebSocketResponseData::Pong {})
+            };
+            responses.insert(id, resp);
+        }
+        for &id in &ids {
+            match responses.take(id).unwrap() {
+                Ok(resp) => {
+                    assert_ne!(id, ids[1]);
+                    assert_eq!(resp.request_id, Some(id.into()));
+                }
+                Err(RunCommandError::ModelingApiFailure { request_id, .. }) => {
+                    assert_eq!(id, ids[1]);
+                    assert_eq!(request_id, Some(id.into()));
+                }
+                Err(e) => panic!("unexpected error {e}"),
+            }
+        }
+        // Each response is only taken once.
+        assert!(ids.iter().all(|&id| responses.take(id).is_none()));
+    }
+
+    #[test]
+    fn files_are_sent_as_bson() {
+        use kittycad_modeling_cmds::{
+            format::InputFormat, shared::FileImportFormat, ImportFile, ImportFiles, SelectClear,
+        };
+
+        let cmd_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let import = WebSocketRequest::ModelingCmdReq(ModelingCmdReq {
+            cmd: ModelingCmd::ImportFiles(ImportFiles {
+                files: vec![ImportFile {
+                    path: "cube.stl".to_owned(),
+                    data: vec![0, 1, 2, 255],
+                }],
+                format: InputFormat::from(FileImportFormat::Stl),
+            }),
+            cmd_id,
+        });
+        let WsMsg::Binary(bytes) = encode_request(&import).unwrap() else {
+            panic!("importing files should be sent as binary");
+        };
+        let decoded: WebSocketRequest = bson::from_slice(&bytes).unwrap();
+        let WebSocketRequest::ModelingCmdReq(decoded) = decoded else {
+            panic!("should decode as a modeling command");
+        };
+        assert_eq!(decoded.cmd_id, cmd_id);
+        let ModelingCmd::ImportFiles(decoded) = decoded.cmd else {
+            panic!("should decode as ImportFiles");
+        };
+        assert_eq!(decoded.files[0].data, vec![0, 1, 2, 255]);
+
+        let clear = WebSocketRequest::ModelingCmdReq(ModelingCmdReq {
+            cmd: ModelingCmd::SelectClear(SelectClear {}),
+            cmd_id,
+        });
+        assert!(matches!(encode_request(&clear).unwrap(), WsMsg::Text(_)));
+    }
+
+    #[test]
+    fn progress_is_sent_until_the_response() {
+        let cmd_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let resp = |resp| WebSocketResponse::success(Some(cmd_id.into()), resp);
+        let progress = |progress| resp(OkWebSocketResponseData::ModelingProgress { progress });
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut responses = Responses::default();
+        responses.progress.insert(cmd_id, tx);
+        responses.insert(cmd_id, progress(0.25));
+        responses.insert(cmd_id, progress(0.75));
+        // Progress isn't mistaken for the response.
+        assert!(responses.take(cmd_id).is_none());
+        responses.insert(
+            cmd_id,
+            resp(OkWebSocketResponseData::Modeling {
+                modeling_response: OkModelingCmdResponse::Empty,
+            }),
+        );
+        assert!(responses.take(cmd_id).unwrap().is_ok());
+        assert_eq!(rx.try_recv(), Ok(0.25));
+        assert_eq!(rx.try_recv(), Ok(0.75));
+        // Once the response has been taken, the progress stops.
+        assert_eq!(rx.try_recv(), Err(mpsc::error::TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn cancelled_responses_are_dropped() {
+        let resp = |id: ModelingCmdId| WebSocketResponse::success(Some(id.into()), OkWebSocketResponseData::Pong {});
+        let kept: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let cancelled: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b02".parse().unwrap();
+        let mut responses = Responses::default();
+        responses.cancel(cancelled);
+        responses.insert(kept, resp(kept));
+        responses.insert(cancelled, resp(cancelled));
+        assert!(responses.received.contains_key(&kept));
+        assert!(!responses.received.contains_key(&cancelled));
+        // The cancellation has been used up by dropping the response.
+        assert!(responses.cancelled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn response_is_dropped_when_caller_stops_waiting() {
+        let cmd_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let mut responses = Responses::default();
+        let (mut responder, rx) = oneshot::channel::<()>();
+        drop(rx);
+        let resp = wait_for_response(
+            cmd_id,
+            &mut responses,
+            &mut futures::stream::pending(),
+            Duration::from_secs(10),
+            &mut responder,
+        )
+        .await;
+        assert!(matches!(resp, Err(RunCommandError::Cancelled)));
+        responses.insert(cmd_id, pong(cmd_id));
+        assert!(responses.received.is_empty());
+        assert!(responses.cancelled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn response_is_dropped_after_waiting_for_cancelled_command() {
+        let cmd_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let mut responses = Responses::default();
+        responses.cancel(cmd_id);
+        let (mut responder, _rx) = oneshot::channel::<()>();
+        let resp = wait_for_response(
+            cmd_id,
+            &mut responses,
+            &mut futures::stream::pending(),
+            Duration::from_secs(10),
+            &mut responder,
+        )
+        .await;
+        assert!(matches!(resp, Err(RunCommandError::Cancelled)));
+        // Waiting didn't use up the cancellation, so the response is still dropped when it arrives.
+        responses.insert(cmd_id, pong(cmd_id));
+        assert!(responses.received.is_empty());
+        assert!(responses.cancelled.is_empty());
+    }
+
+    #[test]
+    fn cancelling_after_the_response_arrived_drops_it() {
+        let cmd_id: ModelingCmdId = "a6b6a6a5-3e2b-4a8a-9e2a-7d0c6a4f5b01".parse().unwrap();
+        let mut responses = Responses::default();
+        responses.insert(cmd_id, pong(cmd_id));
+        responses.cancel(cmd_id);
+        assert!(responses.received.is_empty());
+        // No other response is coming, so there's nothing left to drop.
+        assert!(responses.cancelled.is_empty());
+    }
+
+    fn pong(cmd_id: ModelingCmdId) -> WebSocketResponse {
+        WebSocketResponse::success(Some(cmd_id.into()), OkWebSocketResponseData::Pong {})
+    }
+}
@@ -2,18 +2,31 @@
 
 use std::time::Duration;
 
-use futures::StreamExt;
+use futures::{
+    future::{BoxFuture, Either},
+    StreamExt,
+};
 use kittycad::{types::error::Error as ApiError, Client};
 use kittycad_modeling_cmds::{
     id::ModelingCmdId,
     ok_response::OkModelingCmdResponse,
-    websocket::{ModelingBatch, ModelingCmdReq},
-    ModelingCmd,
+    shared::ExportFile,
+    websocket::{EngineWarning, ErrorCode, ModelingBatch, ModelingCmdReq},
+    Export, ModelingCmd,
 };
 use tokio::sync::{mpsc, oneshot};
 use uuid::Uuid;
 
 mod actor;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod rate_limit;
+pub mod record;
+mod retry;
+
+#[cfg(feature = "metrics")]
+pub use metrics::MetricsSnapshot;
+pub use retry::RetryPolicy;
 
 /// Parameters for starting a session with the KittyCAD Modeling API.
 pub struct SessionBuilder {
@@ -34,12 +47,22 @@ pub struct SessionBuilder {
     pub await_response_timeout: Option<Duration>,
     /// Show the grid?
     pub show_grid: Option<bool>,
+    /// Most modeling commands (or batches) to send per second.
+    /// Sends beyond this rate wait until they're allowed, instead of bursting and tripping the engine's rate limits.
+    /// Defaults to no limit.
+    pub max_commands_per_second: Option<u32>,
+    /// Retry commands which fail because of a temporary problem in the engine.
+    /// Defaults to never retrying.
+    pub retry_policy: Option<RetryPolicy>,
 }
 
 /// An active session with the KittyCAD Modeling API.
 /// TODO: This needs some sort of buffering. It should allow users to send many requests in a row and then wait for the responses.
 pub struct Session {
     actor_tx: mpsc::Sender<actor::Request>,
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "metrics")]
+    metrics: metrics::Metrics,
 }
 
 impl Session {
@@ -54,6 +77,8 @@ impl Session {
             buffer_reqs,
             await_response_timeout,
             show_grid,
+            max_commands_per_second,
+            retry_policy,
         }: SessionBuilder,
     ) -> Result<Self, ApiError> {
         // TODO: establish WebRTC connections for the user.
@@ -82,13 +107,30 @@ impl Session {
         .await
         .split();
         let (actor_tx, actor_rx) = mpsc::channel(buffer_reqs.unwrap_or(10));
+        #[cfg(feature = "metrics")]
+        let metrics = metrics::Metrics::default();
         tokio::task::spawn(actor::start(
             actor_rx,
             write_to_ws,
             read_from_ws,
             await_response_timeout.unwrap_or(Duration::from_secs(10)),
+            max_commands_per_second.map(rate_limit::RateLimiter::new),
+            #[cfg(feature = "metrics")]
+            metrics.clone(),
         ));
-        Ok(Self { actor_tx })
+        Ok(Self {
+            actor_tx,
+            retry_policy,
+            #[cfg(feature = "metrics")]
+            metrics,
+        })
+    }
+
+    /// Statistics about the commands this session has sent and the responses it got,
+    /// e.g. for a monitoring dashboard which scrapes many sessions.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
     }
 
     /// Send a modeling command and wait for its response.
@@ -97,6 +139,103 @@ impl Session {
         cmd_id: ModelingCmdId,
         cmd: ModelingCmd,
     ) -> Result<OkModelingCmdResponse, RunCommandError> {
+        let (resp, _warnings) = self.run_command_with_warnings(cmd_id, cmd).await?;
+        Ok(resp)
+    }
+
+    /// Send a modeling command and wait for its response.
+    /// Also returns any warnings the engine sent about it, e.g. if the command succeeded
+    /// but the engine had to adjust its parameters.
+    /// If the session has a [`RetryPolicy`], retryable failures are retried before being returned.
+    pub async fn run_command_with_warnings(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> Result<(OkModelingCmdResponse, Vec<EngineWarning>), RunCommandError> {
+        // Only commands which might be retried need a copy kept for retrying.
+        // Others (including large ones like `ImportFiles`) are moved straight into the request.
+        let policy = match self.retry_policy {
+            Some(policy) if (policy.is_idempotent)(&cmd) => policy,
+            _ => return self.send_and_wait(cmd_id, cmd).await,
+        };
+        let session = &*self;
+        let cmd = &cmd;
+        policy.run(cmd, || session.send_and_wait(cmd_id, cmd.clone())).await
+    }
+
+    /// Send a modeling command and wait for its response, calling `on_progress` whenever the engine
+    /// reports how much of the command is done (from 0 to 1), e.g. for a progress bar during a big import.
+    /// The engine only reports progress for some long-running commands, so `on_progress` might never be called.
+    /// Unlike [`Session::run_command`], failures aren't retried, even if the session has a [`RetryPolicy`].
+    pub async fn run_command_with_progress(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+        mut on_progress: impl FnMut(f32),
+    ) -> Result<OkModelingCmdResponse, RunCommandError> {
+        self.send(cmd_id, cmd).await?;
+        // The actor only reads progress from the engine while waiting for a response,
+        // so watching before waiting means no progress is missed.
+        // Watching only once the command has been sent means there's nothing to clean up if sending fails.
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        self.actor_tx
+            .send(actor::Request::WatchProgress(cmd_id, progress_tx))
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)?;
+        let mut resp = std::pin::pin!(self.wait_for_response(cmd_id));
+        let resp = loop {
+            match futures::future::select(resp.as_mut(), std::pin::pin!(progress_rx.recv())).await {
+                Either::Left((resp, _)) => break resp,
+                Either::Right((Some(progress), _)) => on_progress(progress),
+                // The actor stopped sending progress, so the response is ready.
+                Either::Right((None, resp)) => break resp.await,
+            }
+        };
+        // Report any progress which arrived just before the response.
+        while let Ok(progress) = progress_rx.try_recv() {
+            on_progress(progress);
+        }
+        let (resp, _warnings) = resp?;
+        Ok(resp)
+    }
+
+    /// Send many modeling commands, then wait for all their responses.
+    /// Unlike [`Session::run_command`] in a loop, this doesn't wait for each response before sending
+    /// the next command, so the commands' round trips overlap.
+    /// Unlike [`Session::run_batch`], each command is sent separately and gets its own response,
+    /// so one command failing doesn't stop the others from running.
+    /// The responses are in the same order as `cmds`.
+    pub async fn run_commands_pipelined(
+        &mut self,
+        cmds: Vec<(ModelingCmdId, ModelingCmd)>,
+    ) -> Vec<Result<OkModelingCmdResponse, RunCommandError>> {
+        let mut sent = Vec::with_capacity(cmds.len());
+        for (cmd_id, cmd) in cmds {
+            sent.push((cmd_id, self.send(cmd_id, cmd).await));
+        }
+        let mut responses = Vec::with_capacity(sent.len());
+        for (cmd_id, sent) in sent {
+            let resp = match sent {
+                Ok(()) => self.wait_for_response(cmd_id).await.map(|(resp, _warnings)| resp),
+                Err(e) => Err(e),
+            };
+            responses.push(resp);
+        }
+        responses
+    }
+
+    /// Send a modeling command once, and wait for its response.
+    async fn send_and_wait(
+        &self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> Result<(OkModelingCmdResponse, Vec<EngineWarning>), RunCommandError> {
+        self.send(cmd_id, cmd).await?;
+        self.wait_for_response(cmd_id).await
+    }
+
+    /// Send a modeling command, without waiting for its response.
+    async fn send(&self, cmd_id: ModelingCmdId, cmd: ModelingCmd) -> Result<(), RunCommandError> {
         // All messages to the KittyCAD Modeling API will be sent over the WebSocket as Text.
         // The text will contain JSON representing a `ModelingCmdReq`.
         // This takes in a command and its ID, and makes a WebSocket message containing that command.
@@ -105,7 +244,14 @@ impl Session {
             .send(actor::Request::SendModelingCmd(ModelingCmdReq { cmd, cmd_id }, tx))
             .await
             .map_err(|_| RunCommandError::ActorFailed)?;
-        rx.await.map_err(|_| RunCommandError::ActorFailed)??;
+        rx.await.map_err(|_| RunCommandError::ActorFailed)?
+    }
+
+    /// Wait for the response to a modeling command which has already been sent.
+    async fn wait_for_response(
+        &self,
+        cmd_id: ModelingCmdId,
+    ) -> Result<(OkModelingCmdResponse, Vec<EngineWarning>), RunCommandError> {
         let (tx, rx) = oneshot::channel();
         self.actor_tx
             .send(actor::Request::GetResponse(cmd_id, tx))
@@ -115,6 +261,84 @@ impl Session {
         Ok(resp)
     }
 
+    /// Export entities to files, and wait for the engine to send the exported files back.
+    /// The engine sends the files separately to the `Export` command's response,
+    /// so this waits for both of them.
+    pub async fn export_blocking(&mut self, cmd: Export) -> Result<Vec<ExportFile>, RunCommandError> {
+        let cmd_id = ModelingCmdId(Uuid::new_v4());
+        let (tx, rx) = oneshot::channel();
+        self.actor_tx
+            .send(actor::Request::SendModelingCmd(
+                ModelingCmdReq {
+                    cmd: cmd.into(),
+                    cmd_id,
+                },
+                tx,
+            ))
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)?;
+        rx.await.map_err(|_| RunCommandError::ActorFailed)??;
+        let (tx, rx) = oneshot::channel();
+        self.actor_tx
+            .send(actor::Request::GetExportFiles(cmd_id, tx))
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)?;
+        let files = rx.await.map_err(|_| RunCommandError::ActorFailed)??;
+        Ok(files)
+    }
+
+    /// Drop the response to a command, e.g. because the user changed their mind about an expensive `Loft`.
+    /// To stop waiting for a command, drop its `run_command` future (e.g. with a timeout or `select!`).
+    /// That drops the command's response when it arrives, and because this takes `&mut self`,
+    /// the future has to be dropped before this can be called anyway.
+    /// This is for when the future was dropped before it started waiting, e.g. while sending the command,
+    /// so its response would otherwise be kept forever.
+    /// Don't wait for a command's response after cancelling it. That returns [`RunCommandError::Cancelled`]
+    /// if the response hadn't arrived yet, but times out if it had, because the response is gone.
+    /// Note this doesn't stop the engine running the command, because the engine doesn't support that yet.
+    pub async fn cancel(&mut self, cmd_id: ModelingCmdId) -> Result<(), RunCommandError> {
+        self.actor_tx
+            .send(actor::Request::Cancel(cmd_id))
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)
+    }
+
+    /// Run a batch of commands at once, and wait for each command's response.
+    /// The responses are in the same order as `requests`.
+    /// If one command fails, the engine won't run any after it, and they'll get
+    /// [`RunCommandError::NotRunInBatch`].
+    pub async fn run_batch(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        batch_id: ModelingCmdId,
+    ) -> Result<Vec<Result<OkModelingCmdResponse, RunCommandError>>, RunCommandError> {
+        let cmd_ids = requests.iter().map(|req| req.cmd_id).collect();
+        let (tx, rx) = oneshot::channel();
+        self.actor_tx
+            .send(actor::Request::SendModelingBatch(
+                ModelingBatch {
+                    requests,
+                    batch_id,
+                    responses: true,
+                },
+                tx,
+            ))
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)?;
+        rx.await.map_err(|_| RunCommandError::ActorFailed)??;
+        let (tx, rx) = oneshot::channel();
+        self.actor_tx
+            .send(actor::Request::GetBatchResponses {
+                batch_id,
+                cmd_ids,
+                responder: tx,
+            })
+            .await
+            .map_err(|_| RunCommandError::ActorFailed)?;
+        let responses = rx.await.map_err(|_| RunCommandError::ActorFailed)??;
+        Ok(responses)
+    }
+
     /// Run a batch of commands at once.
     pub async fn run_batch_no_responses(
         &mut self,
@@ -138,21 +362,80 @@ impl Session {
     }
 }
 
+/// Something which can run modeling commands, e.g. a [`Session`] connected to the KittyCAD API.
+/// Code which sends modeling commands should take this trait instead of a concrete `Session`,
+/// so that it can be tested against a fake, or wrapped in middleware (e.g. for recording or rate-limiting).
+///
+/// The methods return boxed futures so that this trait can be used as `&mut dyn CommandRunner`.
+pub trait CommandRunner: Send {
+    /// Send a modeling command and wait for its response.
+    fn run_command(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> BoxFuture<'_, Result<OkModelingCmdResponse, RunCommandError>>;
+
+    /// Run a batch of commands at once.
+    fn run_batch_no_responses(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        batch_id: ModelingCmdId,
+    ) -> BoxFuture<'_, Result<(), RunCommandError>>;
+}
+
+impl CommandRunner for Session {
+    fn run_command(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> BoxFuture<'_, Result<OkModelingCmdResponse, RunCommandError>> {
+        Box::pin(Session::run_command(self, cmd_id, cmd))
+    }
+
+    fn run_batch_no_responses(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        batch_id: ModelingCmdId,
+    ) -> BoxFuture<'_, Result<(), RunCommandError>> {
+        Box::pin(Session::run_batch_no_responses(self, requests, batch_id))
+    }
+}
+
+impl<R: CommandRunner + ?Sized> CommandRunner for &mut R {
+    fn run_command(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> BoxFuture<'_, Result<OkModelingCmdResponse, RunCommandError>> {
+        (**self).run_command(cmd_id, cmd)
+    }
+
+    fn run_batch_no_responses(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        batch_id: ModelingCmdId,
+    ) -> BoxFuture<'_, Result<(), RunCommandError>> {
+        (**self).run_batch_no_responses(requests, batch_id)
+    }
+}
+
 /// Errors from running a modeling command.
+/// The API client's and WebSocket's errors are boxed, because they're much bigger than the others,
+/// and every `Result` returning this error would be that big too.
 #[derive(thiserror::Error, Debug)]
 pub enum RunCommandError {
     /// Error from the KittyCAD API client.
     #[error("error from KittyCAD API client: {0}")]
-    ApiError(#[from] ApiError),
+    ApiError(#[from] Box<ApiError>),
     /// Request body could not be serialized.
     #[error("the given body couldn't be serialized: {0}")]
     InvalidRequestBody(#[from] serde_json::Error),
     /// Could not send message via WebSocket.
     #[error("could not send via WebSocket: {0}")]
-    WebSocketSend(tokio_tungstenite::tungstenite::Error),
+    WebSocketSend(Box<tokio_tungstenite::tungstenite::Error>),
     /// Could not receive message via WebSocket.
     #[error("could not receive via WebSocket: {0}")]
-    WebSocketRecv(tokio_tungstenite::tungstenite::Error),
+    WebSocketRecv(Box<tokio_tungstenite::tungstenite::Error>),
     /// Modeling API request failed.
     #[error("modeling API returned an error on request {request_id:?}: {errors:?}")]
     ModelingApiFailure {
@@ -176,9 +459,45 @@ pub enum RunCommandError {
     /// Actor has failed
     #[error("Websocket actor has failed, restart the session")]
     ActorFailed,
+    /// The command was cancelled before its response arrived.
+    #[error("the command was cancelled")]
+    Cancelled,
+    /// An earlier command in the batch failed, so this command was never run.
+    #[error("an earlier command in the batch failed, so this command was not run")]
+    NotRunInBatch,
+    /// Could not encode the request as BSON.
+    #[error("could not encode the request as BSON: {0}")]
+    BsonEncode(bson::ser::Error),
+    /// Could not write to the session recording.
+    #[error("could not write to the session recording: {0}")]
+    RecordingWrite(std::io::Error),
+    /// Could not replay the command from a session recording.
+    #[error("could not replay from the session recording: {0}")]
+    Replay(String),
+}
+
+impl From<ApiError> for RunCommandError {
+    fn from(e: ApiError) -> Self {
+        Self::ApiError(Box::new(e))
+    }
 }
 
 impl RunCommandError {
+    /// Might the command succeed if it's sent again?
+    /// True if the engine or API failed because of a temporary problem on their side,
+    /// rather than because the command itself was wrong.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RunCommandError::ModelingApiFailure { errors, .. } => {
+                !errors.is_empty()
+                    && errors
+                        .iter()
+                        .all(|e| matches!(e.error_code, ErrorCode::InternalEngine | ErrorCode::InternalApi))
+            }
+            _ => false,
+        }
+    }
+
     /// Does this error indicate that the session has become unhealthy and should be restarted
     /// (i.e. ended and started again)?
     pub fn should_end_session(&self) -> bool {
@@ -187,12 +506,214 @@ impl RunCommandError {
             RunCommandError::ActorFailed => true,
             RunCommandError::ApiError(_) => false,
             RunCommandError::InvalidRequestBody(_) => false,
+            RunCommandError::BsonEncode(_) => false,
             RunCommandError::WebSocketSend(_) => false,
             RunCommandError::WebSocketRecv(_) => false,
             RunCommandError::ModelingApiFailure { .. } => false,
             RunCommandError::WrongId => false,
             RunCommandError::TimeOutWaitingForResponse => false,
             RunCommandError::ServerSentWrongType => false,
+            RunCommandError::Cancelled => false,
+            RunCommandError::NotRunInBatch => false,
+            RunCommandError::RecordingWrite(_) => false,
+            RunCommandError::Replay(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc as fake_ws, SinkExt};
+    use kittycad_modeling_cmds::{
+        format::OutputFormat,
+        output,
+        websocket::{OkWebSocketResponseData, RawFile, WebSocketRequest, WebSocketResponse},
+        SelectGet,
+    };
+    use tokio::time::Instant;
+    use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMsg};
+
+    use super::*;
+
+    type FakeEngine = (
+        fake_ws::UnboundedReceiver<WsMsg>,
+        fake_ws::UnboundedSender<Result<WsMsg, WsError>>,
+    );
+
+    /// A session connected to a fake engine, instead of the KittyCAD API.
+    /// Returns the session, and the fake engine's ends of the WebSocket: what the session sends, and what it reads.
+    fn fake_session(max_commands_per_second: Option<u32>) -> (Session, FakeEngine) {
+        let (write_to_ws, sent) = fake_ws::unbounded();
+        let (engine, read_from_ws) = fake_ws::unbounded();
+        let (actor_tx, actor_rx) = mpsc::channel(10);
+        #[cfg(feature = "metrics")]
+        let metrics = metrics::Metrics::default();
+        tokio::task::spawn(actor::start(
+            actor_rx,
+            write_to_ws.sink_map_err(|_| WsError::ConnectionClosed),
+            read_from_ws,
+            Duration::from_secs(10),
+            max_commands_per_second.map(rate_limit::RateLimiter::new),
+            #[cfg(feature = "metrics")]
+            metrics.clone(),
+        ));
+        let session = Session {
+            actor_tx,
+            retry_policy: None,
+            #[cfg(feature = "metrics")]
+            metrics,
+        };
+        (session, (sent, engine))
+    }
+
+    /// Read the next command the session sent to the fake engine.
+    async fn next_cmd(sent: &mut fake_ws::UnboundedReceiver<WsMsg>) -> ModelingCmdReq {
+        let Some(WsMsg::Text(text)) = sent.next().await else {
+            panic!("the session should send the command as text");
+        };
+        let WebSocketRequest::ModelingCmdReq(req) = serde_json::from_str(&text).unwrap() else {
+            panic!("the session should send a modeling command, not {text}");
+        };
+        req
+    }
+
+    /// Send a response from the fake engine to the session.
+    fn respond(engine: &fake_ws::UnboundedSender<Result<WsMsg, WsError>>, resp: WebSocketResponse) {
+        let text = serde_json::to_string(&resp).unwrap();
+        engine.unbounded_send(Ok(WsMsg::Text(text))).unwrap();
+    }
+
+    #[tokio::test]
+    async fn pipelined_responses_arrive_out_of_order() {
+        let (mut session, (mut sent, engine)) = fake_session(None);
+        let ids: Vec<ModelingCmdId> = (1..=3).map(|i| ModelingCmdId(Uuid::from_u128(i))).collect();
+        let cmds = ids.iter().map(|&id| (id, ModelingCmd::from(SelectGet {}))).collect();
+        // The fake engine waits until every command has been sent, then responds to them in reverse order.
+        // Each response contains its command's ID, so the test can tell which is which.
+        let fake_engine = async {
+            let mut received = Vec::new();
+            for _ in 0..ids.len() {
+                received.push(next_cmd(&mut sent).await.cmd_id);
+            }
+            for &cmd_id in received.iter().rev() {
+                let modeling_response = OkModelingCmdResponse::SelectGet(output::SelectGet {
+                    entity_ids: vec![cmd_id.into()],
+                });
+                let resp = OkWebSocketResponseData::Modeling { modeling_response };
+                respond(&engine, WebSocketResponse::success(Some(cmd_id.into()), resp));
+            }
+            received
+        };
+
+        let (responses, received) = futures::join!(session.run_commands_pipelined(cmds), fake_engine);
+        assert_eq!(received, ids);
+        assert_eq!(responses.len(), ids.len());
+        for (resp, id) in responses.into_iter().zip(&ids) {
+            let Ok(OkModelingCmdResponse::SelectGet(resp)) = resp else {
+                panic!("unexpected response {resp:?}");
+            };
+            assert_eq!(resp.entity_ids, vec![Uuid::from(*id)]);
+        }
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_before_the_response() {
+        let (mut session, (mut sent, engine)) = fake_session(None);
+        let cmd_id = ModelingCmdId(Uuid::new_v4());
+        let fake_engine = async {
+            let cmd_id = next_cmd(&mut sent).await.cmd_id;
+            for progress in [0.25, 0.75] {
+                let progress = OkWebSocketResponseData::ModelingProgress { progress };
+                respond(&engine, WebSocketResponse::success(Some(cmd_id.into()), progress));
+            }
+            let done = OkWebSocketResponseData::Modeling {
+                modeling_response: OkModelingCmdResponse::Empty,
+            };
+            respond(&engine, WebSocketResponse::success(Some(cmd_id.into()), done));
+        };
+
+        let mut progress = Vec::new();
+        let run = session.run_command_with_progress(cmd_id, ModelingCmd::from(SelectGet {}), |p| progress.push(p));
+        let (resp, ()) = futures::join!(run, fake_engine);
+        assert!(
+            matches!(resp, Ok(OkModelingCmdResponse::Empty)),
+            "unexpected response {resp:?}"
+        );
+        assert_eq!(progress, vec![0.25, 0.75]);
+    }
+
+    #[tokio::test]
+    async fn exported_files_arrive_as_bson() {
+        let (mut session, (mut sent, engine)) = fake_session(None);
+        let fake_engine = async {
+            let cmd_id = next_cmd(&mut sent).await.cmd_id;
+            // The engine acknowledges the command as usual, then sends the files in a binary message.
+            let ack = OkWebSocketResponseData::Modeling {
+                modeling_response: OkModelingCmdResponse::Export(output::Export { files: Vec::new() }),
+            };
+            respond(&engine, WebSocketResponse::success(Some(cmd_id.into()), ack));
+            let files = OkWebSocketResponseData::Export {
+                files: vec![RawFile {
+                    name: "cube.step".to_owned(),
+                    contents: b"ISO-10303-21;".to_vec(),
+                }],
+            };
+            let bytes = bson::to_vec(&WebSocketResponse::success(Some(cmd_id.into()), files)).unwrap();
+            engine.unbounded_send(Ok(WsMsg::Binary(bytes))).unwrap();
+        };
+
+        let export = Export {
+            entity_ids: Vec::new(),
+            format: OutputFormat::step(),
+        };
+        let (files, ()) = futures::join!(session.export_blocking(export), fake_engine);
+        let files = files.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "cube.step");
+        assert_eq!(files[0].contents.0, b"ISO-10303-21;");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn session_paces_a_burst() {
+        const LIMIT: u32 = 10;
+        const SENDS: usize = 30;
+        let (mut session, (mut sent, engine)) = fake_session(Some(LIMIT));
+        let cmds = (0..SENDS)
+            .map(|_| (ModelingCmdId(Uuid::new_v4()), ModelingCmd::from(SelectGet {})))
+            .collect();
+        // The clock is paused, so the fake engine sees exactly when each command was sent.
+        let start = Instant::now();
+        let fake_engine = async {
+            let mut sent_at = Vec::new();
+            for _ in 0..SENDS {
+                let cmd_id = next_cmd(&mut sent).await.cmd_id;
+                sent_at.push(start.elapsed());
+                let done = OkWebSocketResponseData::Modeling {
+                    modeling_response: OkModelingCmdResponse::Empty,
+                };
+                respond(&engine, WebSocketResponse::success(Some(cmd_id.into()), done));
+            }
+            sent_at
+        };
+
+        let (responses, sent_at) = futures::join!(session.run_commands_pipelined(cmds), fake_engine);
+        assert!(responses.iter().all(Result::is_ok));
+        // A second's worth of commands are sent straight away...
+        let burst = LIMIT as usize;
+        assert!(sent_at[..burst].iter().all(Duration::is_zero), "{sent_at:?}");
+        // ...then the rest are sent at the limit.
+        let interval = Duration::from_secs(1) / LIMIT;
+        for gap in sent_at[burst - 1..].windows(2).map(|w| w[1] - w[0]) {
+            assert!(
+                gap >= interval,
+                "commands were sent {gap:?} apart, faster than {LIMIT}/s"
+            );
         }
+        let expected = interval * (SENDS - burst) as u32;
+        let took = sent_at[SENDS - 1];
+        assert!(
+            took < expected.mul_f64(1.1),
+            "took {took:?}, much slower than {LIMIT}/s"
+        );
     }
 }
@@ -0,0 +1,245 @@
+//! Statistics about the commands a session sends, for monitoring many sessions at once.
+//! As well as keeping them for [`crate::Session::metrics_snapshot`], they're recorded with the `metrics` crate,
+//! so an application which installs a recorder (e.g. a Prometheus exporter) gets them from every session:
+//! - `modeling_session_commands_sent_total` counter, labelled by `command` name.
+//! - `modeling_session_responses_received_total` counter.
+//! - `modeling_session_errors_total` counter, labelled by `error_code` (including `TimedOut`).
+//! - `modeling_session_response_latency_seconds` histogram.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use kittycad_modeling_cmds::{
+    id::ModelingCmdId,
+    websocket::{ApiError, BatchResponse, OkWebSocketResponseData, WebSocketResponse},
+    ModelingCmd, ModelingCmdEndpoint,
+};
+use tokio::time::Instant;
+
+/// How many of the most recent latencies to keep, for calculating percentiles.
+const LATENCY_SAMPLES: usize = 1024;
+
+/// Statistics about a session, from [`crate::Session::metrics_snapshot`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// How many of each command were sent, keyed by the command's name (e.g. `Extrude`).
+    /// Commands sent in a batch are counted individually.
+    pub commands_sent: BTreeMap<String, u64>,
+    /// How many responses were received, successful or not.
+    /// The engine sends one response for a whole batch.
+    pub responses_received: u64,
+    /// How many errors the engine sent, keyed by error code (e.g. `BadRequest`).
+    /// Responses which never arrived are counted under `TimedOut`.
+    pub errors: BTreeMap<String, u64>,
+    /// Median time between sending a command (or batch) and receiving its response,
+    /// over the most recent responses.
+    pub latency_p50: Option<Duration>,
+    /// 99th percentile time between sending a command (or batch) and receiving its response,
+    /// over the most recent responses.
+    pub latency_p99: Option<Duration>,
+}
+
+/// Collects statistics as the actor sends and receives.
+/// Clones share the same statistics, so the [`crate::Session`] can read what the actor records.
+#[derive(Clone, Default)]
+pub(crate) struct Metrics(Arc<Mutex<State>>);
+
+#[derive(Default)]
+struct State {
+    commands_sent: BTreeMap<String, u64>,
+    responses_received: u64,
+    errors: BTreeMap<String, u64>,
+    /// When each request still waiting for a response was sent.
+    sent_at: HashMap<ModelingCmdId, Instant>,
+    latencies: VecDeque<Duration>,
+}
+
+impl State {
+    fn error(&mut self, error_code: String) {
+        ::metrics::counter!("modeling_session_errors_total", "error_code" => error_code.clone()).increment(1);
+        *self.errors.entry(error_code).or_default() += 1;
+    }
+}
+
+impl Metrics {
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The statistics are still usable even if a panic interrupted an update.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record that a request (a single command, or a batch of them) is being sent.
+    pub fn sent<'a>(&self, request_id: ModelingCmdId, cmds: impl IntoIterator<Item = &'a ModelingCmd>) {
+        let mut state = self.state();
+        for cmd in cmds {
+            let name = ModelingCmdEndpoint::from(cmd).to_string();
+            ::metrics::counter!("modeling_session_commands_sent_total", "command" => name.clone()).increment(1);
+            *state.commands_sent.entry(name).or_default() += 1;
+        }
+        state.sent_at.insert(request_id, Instant::now());
+    }
+
+    /// Record that the response to a request arrived.
+    pub fn received(&self, request_id: ModelingCmdId, resp: &WebSocketResponse) {
+        let mut state = self.state();
+        state.responses_received += 1;
+        ::metrics::counter!("modeling_session_responses_received_total").increment(1);
+        if let Some(sent_at) = state.sent_at.remove(&request_id) {
+            let latency = sent_at.elapsed();
+            ::metrics::histogram!("modeling_session_response_latency_seconds").record(latency);
+            if state.latencies.len() == LATENCY_SAMPLES {
+                state.latencies.pop_front();
+            }
+            state.latencies.push_back(latency);
+        }
+        let errors: Vec<&ApiError> = match resp {
+            WebSocketResponse::Failure(failure) => failure.errors.iter().collect(),
+            WebSocketResponse::Success(success) => match &success.resp {
+                OkWebSocketResponseData::ModelingBatch { responses } => responses
+                    .values()
+                    .flat_map(|resp| match resp {
+                        BatchResponse::Failure { errors } => errors.as_slice(),
+                        BatchResponse::Success { .. } => [].as_slice(),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
+        };
+        for error in errors {
+            state.error(error.error_code.to_string());
+        }
+    }
+
+    /// Record that the response to a request never arrived.
+    pub fn timed_out(&self, request_id: ModelingCmdId) {
+        let mut state = self.state();
+        state.sent_at.remove(&request_id);
+        state.error("TimedOut".to_owned());
+    }
+
+    /// Stop waiting for the response to a request, e.g. because it was cancelled.
+    pub fn forget(&self, request_id: ModelingCmdId) {
+        self.state().sent_at.remove(&request_id);
+    }
+
+    /// The statistics recorded so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let state = self.state();
+        let mut latencies: Vec<Duration> = state.latencies.iter().copied().collect();
+        latencies.sort_unstable();
+        // Nearest-rank percentile.
+        let percentile = |p: usize| {
+            let rank = (latencies.len() * p).div_ceil(100);
+            latencies.get(rank.saturating_sub(1)).copied()
+        };
+        MetricsSnapshot {
+            commands_sent: state.commands_sent.clone(),
+            responses_received: state.responses_received,
+            errors: state.errors.clone(),
+            latency_p50: percentile(50),
+            latency_p99: percentile(99),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kittycad_modeling_cmds::{websocket::ErrorCode, SelectClear, SelectGet};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn counters_increment() {
+        let metrics = Metrics::default();
+        let session_view = metrics.clone();
+        assert_eq!(session_view.snapshot(), MetricsSnapshot::default());
+
+        let ok_id = ModelingCmdId(Uuid::new_v4());
+        let failed_id = ModelingCmdId(Uuid::new_v4());
+        let lost_id = ModelingCmdId(Uuid::new_v4());
+        let clear = ModelingCmd::SelectClear(SelectClear {});
+        let get = ModelingCmd::SelectGet(SelectGet {});
+        metrics.sent(ok_id, [&clear]);
+        metrics.sent(failed_id, [&clear, &get]);
+        metrics.sent(lost_id, [&get]);
+        metrics.received(
+            ok_id,
+            &WebSocketResponse::success(Some(ok_id.into()), OkWebSocketResponseData::Pong {}),
+        );
+        metrics.received(
+            failed_id,
+            &WebSocketResponse::failure(
+                Some(failed_id.into()),
+                vec![ApiError {
+                    error_code: ErrorCode::BadRequest,
+                    message: "nothing selected".to_owned(),
+                }],
+            ),
+        );
+        metrics.timed_out(lost_id);
+
+        let snapshot = session_view.snapshot();
+        assert_eq!(
+            snapshot.commands_sent,
+            BTreeMap::from([("SelectClear".to_owned(), 2), ("SelectGet".to_owned(), 2)])
+        );
+        assert_eq!(snapshot.responses_received, 2);
+        assert_eq!(
+            snapshot.errors,
+            BTreeMap::from([("BadRequest".to_owned(), 1), ("TimedOut".to_owned(), 1)])
+        );
+        assert!(snapshot.latency_p50.is_some());
+        assert!(snapshot.latency_p50 <= snapshot.latency_p99);
+    }
+
+    #[test]
+    fn recorded_with_metrics_crate() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        ::metrics::with_local_recorder(&recorder, || {
+            let metrics = Metrics::default();
+            let ok_id = ModelingCmdId(Uuid::new_v4());
+            let lost_id = ModelingCmdId(Uuid::new_v4());
+            let clear = ModelingCmd::SelectClear(SelectClear {});
+            metrics.sent(ok_id, [&clear, &clear]);
+            metrics.sent(lost_id, [&clear]);
+            metrics.received(
+                ok_id,
+                &WebSocketResponse::success(Some(ok_id.into()), OkWebSocketResponseData::Pong {}),
+            );
+            metrics.timed_out(lost_id);
+        });
+
+        // Name each metric like Prometheus would, e.g. `errors_total{error_code=TimedOut}`.
+        let recorded: BTreeMap<String, DebugValue> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _unit, _description, value)| {
+                let key = key.key();
+                let labels: Vec<String> = key.labels().map(|l| format!("{}={}", l.key(), l.value())).collect();
+                let name = if labels.is_empty() {
+                    key.name().to_owned()
+                } else {
+                    format!("{}{{{}}}", key.name(), labels.join(","))
+                };
+                (name, value)
+            })
+            .collect();
+        let counter = |name: &str| match recorded.get(name) {
+            Some(DebugValue::Counter(count)) => *count,
+            other => panic!("{name} should be a counter, but it's {other:?}"),
+        };
+        assert_eq!(counter("modeling_session_commands_sent_total{command=SelectClear}"), 3);
+        assert_eq!(counter("modeling_session_responses_received_total"), 1);
+        assert_eq!(counter("modeling_session_errors_total{error_code=TimedOut}"), 1);
+        assert!(matches!(
+            recorded.get("modeling_session_response_latency_seconds"),
+            Some(DebugValue::Histogram(latencies)) if latencies.len() == 1
+        ));
+    }
+}
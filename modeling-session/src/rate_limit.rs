@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Token bucket which paces how often the actor sends to the engine.
+/// Holds up to one second's worth of tokens, so short bursts are allowed,
+/// but the average rate never goes above the limit.
+pub struct RateLimiter {
+    per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Allow at most `per_second` sends every second.
+    pub fn new(per_second: u32) -> Self {
+        let per_second = f64::from(per_second.max(1));
+        Self {
+            per_second,
+            tokens: per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let earned = now.duration_since(self.last_refill).as_secs_f64() * self.per_second;
+        self.tokens = (self.tokens + earned).min(self.per_second);
+        self.last_refill = now;
+    }
+
+    /// How long until a send is allowed. Zero if it's allowed right now.
+    pub fn time_until_available(&mut self) -> Duration {
+        self.refill();
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.per_second)
+        }
+    }
+
+    /// Wait until a send is allowed, then use up one token.
+    pub async fn acquire(&mut self) {
+        let wait = self.time_until_available();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+            self.refill();
+        }
+        self.tokens -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The clock is paused, so sleeps finish as soon as the test is idle, and elapsed time is exact.
+    #[tokio::test(start_paused = true)]
+    async fn burst_is_paced() {
+        const LIMIT: u32 = 50;
+        const SENDS: u32 = 100;
+        let mut limiter = RateLimiter::new(LIMIT);
+        let start = Instant::now();
+        for _ in 0..SENDS {
+            limiter.acquire().await;
+        }
+        // The first second's worth of sends can go immediately, the rest have to wait for tokens.
+        let min_expected = Duration::from_secs_f64(f64::from(SENDS - LIMIT) / f64::from(LIMIT));
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= min_expected,
+            "{SENDS} sends took {elapsed:?}, which is faster than {LIMIT}/s allows"
+        );
+        assert!(
+            elapsed < min_expected.mul_f64(1.1),
+            "{SENDS} sends took {elapsed:?}, which is much slower than {LIMIT}/s"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Record every command sent during a session, and replay them later without an engine.
+//! The recording is a JSON Lines file: one [`RecordedEntry`] per line.
+
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Write},
+};
+
+use futures::future::BoxFuture;
+use kittycad_modeling_cmds::{
+    id::ModelingCmdId,
+    ok_response::OkModelingCmdResponse,
+    websocket::{ApiError, ModelingBatch, ModelingCmdReq},
+    ModelingCmd,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{CommandRunner, RunCommandError};
+
+/// A command (or batch of commands) sent during a session, and what came back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedEntry {
+    /// A single modeling command.
+    Command {
+        /// The command that was sent.
+        req: ModelingCmdReq,
+        /// What running the command returned.
+        result: Result<OkModelingCmdResponse, RecordedError>,
+    },
+    /// A batch of modeling commands, sent without responses.
+    Batch {
+        /// The batch that was sent.
+        batch: ModelingBatch,
+        /// What running the batch returned.
+        result: Result<(), RecordedError>,
+    },
+}
+
+/// A [`RunCommandError`] in a form that can be written to a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedError {
+    /// The Modeling API returned an error.
+    ModelingApiFailure {
+        /// ID of the failed request.
+        request_id: Option<Uuid>,
+        /// Errors that caused the request to fail.
+        errors: Vec<ApiError>,
+    },
+    /// Any other error, e.g. the WebSocket closing. Only its message is kept.
+    Other {
+        /// The error's message.
+        message: String,
+    },
+}
+
+impl From<&RunCommandError> for RecordedError {
+    fn from(e: &RunCommandError) -> Self {
+        match e {
+            RunCommandError::ModelingApiFailure { request_id, errors } => Self::ModelingApiFailure {
+                request_id: *request_id,
+                errors: errors.clone(),
+            },
+            other => Self::Other {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<RecordedError> for RunCommandError {
+    fn from(e: RecordedError) -> Self {
+        match e {
+            RecordedError::ModelingApiFailure { request_id, errors } => Self::ModelingApiFailure { request_id, errors },
+            RecordedError::Other { message } => Self::Replay(message),
+        }
+    }
+}
+
+/// Wraps a [`CommandRunner`] (e.g. a [`crate::Session`]), writing each command it runs
+/// and the result to a log. The log can be replayed with [`ReplaySession`].
+pub struct RecordingSession<S, W> {
+    inner: S,
+    log: W,
+}
+
+impl<S, W> RecordingSession<S, W>
+where
+    W: Write,
+{
+    /// Record every command run by `inner` into `log`.
+    pub fn new(inner: S, log: W) -> Self {
+        Self { inner, log }
+    }
+
+    /// Stop recording, returning the wrapped runner and the log.
+    pub fn into_inner(self) -> (S, W) {
+        (self.inner, self.log)
+    }
+
+    fn record(&mut self, entry: &RecordedEntry) -> Result<(), RunCommandError> {
+        serde_json::to_writer(&mut self.log, entry).map_err(|e| {
+            if e.is_io() {
+                RunCommandError::RecordingWrite(e.into())
+            } else {
+                RunCommandError::InvalidRequestBody(e)
+            }
+        })?;
+        writeln!(self.log).map_err(RunCommandError::RecordingWrite)
+    }
+}
+
+impl<S, W> CommandRunner for RecordingSession<S, W>
+where
+    S: CommandRunner,
+    W: Write + Send,
+{
+    /// Run the command, then record it and its result.
+    /// If the result can't be recorded, that error is returned instead.
+    fn run_command(
+        &mut self,
+        cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> BoxFuture<'_, Result<OkModelingCmdResponse, RunCommandError>> {
+        Box::pin(async move {
+            let result = self.inner.run_command(cmd_id, cmd.clone()).await;
+            self.record(&RecordedEntry::Command {
+                req: ModelingCmdReq { cmd, cmd_id },
+                result: result.as_ref().cloned().map_err(RecordedError::from),
+            })?;
+            result
+        })
+    }
+
+    /// Run the batch, then record it and its result.
+    /// If the result can't be recorded, that error is returned instead.
+    fn run_batch_no_responses(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        batch_id: ModelingCmdId,
+    ) -> BoxFuture<'_, Result<(), RunCommandError>> {
+        Box::pin(async move {
+            let result = self.inner.run_batch_no_responses(requests.clone(), batch_id).await;
+            self.record(&RecordedEntry::Batch {
+                batch: ModelingBatch {
+                    requests,
+                    batch_id,
+                    responses: false,
+                },
+                result: result.as_ref().copied().map_err(RecordedError::from),
+            })?;
+            result
+        })
+    }
+}
+
+/// Serves responses from a recording made by [`RecordingSession`], without touching the network.
+/// Commands must be run in the same order they were recorded. Their IDs may differ from the
+/// recording (e.g. if they were randomly generated), but the commands themselves must match.
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySession {
+    entries: VecDeque<RecordedEntry>,
+}
+
+impl ReplaySession {
+    /// Replay these entries, in order.
+    pub fn new(entries: impl IntoIterator<Item = RecordedEntry>) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+
+    /// Read a recording written by [`RecordingSession`].
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, serde_json::Error> {
+        let mut entries = VecDeque::new();
+        for line in reader.lines() {
+            let line = line.map_err(serde_json::Error::io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push_back(serde_json::from_str(&line)?);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Have all recorded entries been replayed?
+    pub fn is_finished(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn next_command(&mut self, cmd: &ModelingCmd) -> Result<OkModelingCmdResponse, RunCommandError> {
+        match self.entries.pop_front() {
+            Some(RecordedEntry::Command { req, result }) if &req.cmd == cmd => result.map_err(RunCommandError::from),
+            Some(entry) => Err(RunCommandError::Replay(format!(
+                "expected the recording's next entry to be the command {cmd:?}, but it was {entry:?}"
+            ))),
+            None => Err(RunCommandError::Replay(
+                "no more entries in the recording, but another command was run".to_owned(),
+            )),
+        }
+    }
+
+    fn next_batch(&mut self, requests: &[ModelingCmdReq]) -> Result<(), RunCommandError> {
+        let same_cmds = |batch: &ModelingBatch| {
+            batch.requests.len() == requests.len() && batch.requests.iter().zip(requests).all(|(a, b)| a.cmd == b.cmd)
+        };
+        match self.entries.pop_front() {
+            Some(RecordedEntry::Batch { batch, result }) if same_cmds(&batch) => result.map_err(RunCommandError::from),
+            Some(entry) => Err(RunCommandError::Replay(format!(
+                "expected the recording's next entry to be a batch of {} commands, but it was {entry:?}",
+                requests.len()
+            ))),
+            None => Err(RunCommandError::Replay(
+                "no more entries in the recording, but another batch was run".to_owned(),
+            )),
+        }
+    }
+}
+
+impl CommandRunner for ReplaySession {
+    fn run_command(
+        &mut self,
+        _cmd_id: ModelingCmdId,
+        cmd: ModelingCmd,
+    ) -> BoxFuture<'_, Result<OkModelingCmdResponse, RunCommandError>> {
+        Box::pin(std::future::ready(self.next_command(&cmd)))
+    }
+
+    fn run_batch_no_responses(
+        &mut self,
+        requests: Vec<ModelingCmdReq>,
+        _batch_id: ModelingCmdId,
+    ) -> BoxFuture<'_, Result<(), RunCommandError>> {
+        Box::pin(std::future::ready(self.next_batch(&requests)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kittycad_modeling_cmds::{websocket::ErrorCode, SelectClear, SelectGet};
+
+    use super::*;
+
+    fn req(cmd: ModelingCmd) -> ModelingCmdReq {
+        ModelingCmdReq {
+            cmd,
+            cmd_id: Uuid::new_v4().into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn record_then_replay() {
+        let clear = ModelingCmd::SelectClear(SelectClear {});
+        let get = ModelingCmd::SelectGet(SelectGet {});
+        let original = ReplaySession::new(vec![
+            RecordedEntry::Command {
+                req: req(clear.clone()),
+                result: Ok(OkModelingCmdResponse::Empty),
+            },
+            RecordedEntry::Batch {
+                batch: ModelingBatch {
+                    requests: vec![req(clear.clone())],
+                    ..Default::default()
+                },
+                result: Ok(()),
+            },
+            RecordedEntry::Command {
+                req: req(get.clone()),
+                result: Err(RecordedError::ModelingApiFailure {
+                    request_id: None,
+                    errors: vec![ApiError {
+                        error_code: ErrorCode::BadRequest,
+                        message: "nothing selected".to_owned(),
+                    }],
+                }),
+            },
+        ]);
+
+        // Record a session, using the fake above as the engine.
+        let mut recording = RecordingSession::new(original, Vec::new());
+        recording
+            .run_command(Uuid::new_v4().into(), clear.clone())
+            .await
+            .unwrap();
+        recording
+            .run_batch_no_responses(vec![req(clear.clone())], Uuid::new_v4().into())
+            .await
+            .unwrap();
+        recording
+            .run_command(Uuid::new_v4().into(), get.clone())
+            .await
+            .unwrap_err();
+        let (original, log) = recording.into_inner();
+        assert!(original.is_finished());
+
+        // Replaying the recording should give the same results.
+        let mut replay = ReplaySession::from_reader(log.as_slice()).unwrap();
+        let resp = replay.run_command(Uuid::new_v4().into(), clear.clone()).await.unwrap();
+        assert!(matches!(resp, OkModelingCmdResponse::Empty));
+        replay
+            .run_batch_no_responses(vec![req(clear)], Uuid::new_v4().into())
+            .await
+            .unwrap();
+        let err = replay
+            .run_command(Uuid::new_v4().into(), get.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RunCommandError::ModelingApiFailure { errors, .. } if errors.len() == 1));
+        assert!(replay.is_finished());
+
+        // Running more commands than were recorded is an error.
+        let err = replay.run_command(Uuid::new_v4().into(), get).await.unwrap_err();
+        assert!(matches!(err, RunCommandError::Replay(_)));
+    }
+
+    #[tokio::test]
+    async fn failed_log_write_is_a_recording_error() {
+        struct BrokenLog;
+
+        impl Write for BrokenLog {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let clear = ModelingCmd::SelectClear(SelectClear {});
+        let original = ReplaySession::new(vec![RecordedEntry::Command {
+            req: req(clear.clone()),
+            result: Ok(OkModelingCmdResponse::Empty),
+        }]);
+        let mut recording = RecordingSession::new(original, BrokenLog);
+        let err = recording.run_command(Uuid::new_v4().into(), clear).await.unwrap_err();
+        assert!(
+            matches!(&err, RunCommandError::RecordingWrite(e) if e.kind() == std::io::ErrorKind::BrokenPipe),
+            "unexpected error {err}"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::{future::Future, time::Duration};
+
+use kittycad_modeling_cmds::ModelingCmd;
+
+use crate::RunCommandError;
+
+/// How to retry modeling commands which failed because of a temporary problem in the engine.
+/// See [`RunCommandError::is_retryable`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Most times to retry a command, after its first attempt.
+    pub max_retries: u32,
+    /// How long to wait before the first retry.
+    /// Each retry after that waits twice as long as the one before it.
+    pub initial_backoff: Duration,
+    /// Longest time to wait before any retry.
+    pub max_backoff: Duration,
+    /// Which commands are safe to send more than once. Other commands are never retried.
+    /// Defaults to [`ModelingCmd::is_read_only`]. Use your own function to also retry
+    /// commands you know are idempotent.
+    pub is_idempotent: fn(&ModelingCmd) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            is_idempotent: ModelingCmd::is_read_only,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait before the given retry (0 for the first retry).
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Run `attempt` until it succeeds, fails in a way that isn't worth retrying, or runs out of retries.
+    pub(crate) async fn run<T, F, Fut>(&self, cmd: &ModelingCmd, mut attempt: F) -> Result<T, RunCommandError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, RunCommandError>>,
+    {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Err(e) if retries < self.max_retries && e.is_retryable() && (self.is_idempotent)(cmd) => {
+                    tokio::time::sleep(self.backoff(retries)).await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kittycad_modeling_cmds::{
+        websocket::{ApiError, ErrorCode},
+        SelectClear, SelectGet,
+    };
+
+    use super::*;
+
+    fn engine_failure(error_code: ErrorCode) -> RunCommandError {
+        RunCommandError::ModelingApiFailure {
+            request_id: None,
+            errors: vec![ApiError {
+                error_code,
+                message: "the engine is having a bad day".to_owned(),
+            }],
+        }
+    }
+
+    /// Runs a command against a fake engine, which fails with the given errors before succeeding.
+    /// Returns the result and how many times the command was sent.
+    async fn run_flaky(
+        policy: RetryPolicy,
+        cmd: ModelingCmd,
+        mut errors: Vec<RunCommandError>,
+    ) -> (Result<(), RunCommandError>, usize) {
+        errors.reverse();
+        let mut attempts = 0;
+        let result = policy
+            .run(&cmd, || {
+                attempts += 1;
+                let result = errors.pop().map_or(Ok(()), Err);
+                async move { result }
+            })
+            .await;
+        (result, attempts)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_internal_engine_errors() {
+        let errors = vec![
+            engine_failure(ErrorCode::InternalEngine),
+            engine_failure(ErrorCode::InternalEngine),
+        ];
+        let (result, attempts) = run_flaky(fast_policy(), SelectGet {}.into(), errors).await;
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let policy = RetryPolicy {
+            max_retries: 1,
+            ..fast_policy()
+        };
+        let errors = vec![
+            engine_failure(ErrorCode::InternalEngine),
+            engine_failure(ErrorCode::InternalEngine),
+        ];
+        let (result, attempts) = run_flaky(policy, SelectGet {}.into(), errors).await;
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_bad_requests_or_non_idempotent_commands() {
+        let errors = vec![engine_failure(ErrorCode::BadRequest)];
+        let (result, attempts) = run_flaky(fast_policy(), SelectGet {}.into(), errors).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let errors = vec![engine_failure(ErrorCode::InternalEngine)];
+        let (result, attempts) = run_flaky(fast_policy(), SelectClear {}.into(), errors).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            ..Default::default()
+        };
+        let backoffs: Vec<_> = (0..4).map(|retry| policy.backoff(retry)).collect();
+        assert_eq!(backoffs, [100, 200, 350, 350].map(Duration::from_millis).to_vec());
+    }
+}